@@ -9,6 +9,9 @@ struct BackgroundMaterial {
 
 @group(2) @binding(0)
 var<uniform> background: BackgroundMaterial;
+// Dimmed in high contrast mode
+@group(2) @binding(1)
+var<uniform> brightness: f32;
 
 const iterations = 17;
 const formuparam = 0.53;
@@ -61,5 +64,5 @@ fn fragment(
         s = s + stepsize;
     }
     v = mix(vec3<f32>(length(v)), v, saturation); // color_adjust
-    return vec4<f32>(v * 0.0006, 1.0);
+    return vec4<f32>(v * 0.0006 * brightness, 1.0);
 }
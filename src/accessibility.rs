@@ -0,0 +1,236 @@
+use serde::{Deserialize, Serialize};
+
+use crate::prelude::*;
+
+pub const ACCESSIBILITY_SAVE_FILE: &str = "accessibility.ron";
+
+// Relative speeds of the whole simulation that can be selected, slowing the game down
+pub const GAME_SPEEDS: [f32; 4] = [0.5, 0.75, 0.9, 1.0];
+
+// Scale of the user interface with large text
+const LARGE_TEXT_SCALE: f32 = 1.25;
+
+// Brightness of the Star Nest background in high contrast mode
+const HIGH_CONTRAST_BACKGROUND: f32 = 0.25;
+
+// Colors used for the menus and the ship, swapped for colorblind players.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Default, Serialize, Deserialize)]
+pub enum Palette {
+    #[default]
+    Standard,
+    // Deuteranopia and protanopia, avoiding red against green
+    RedGreen,
+    // Tritanopia, avoiding blue against yellow
+    BlueYellow,
+}
+impl Palette {
+    pub const ALL: [Palette; 3] = [Palette::Standard, Palette::RedGreen, Palette::BlueYellow];
+    // `Locale` key of the name
+    pub fn key(&self) -> &'static str {
+        match self {
+            Palette::Standard => "palette-standard",
+            Palette::RedGreen => "palette-red-green",
+            Palette::BlueYellow => "palette-blue-yellow",
+        }
+    }
+    // Colorblind palettes pick from the Okabe-Ito colors
+    pub fn color(&self, tone: MenuTone) -> Color {
+        match (self, tone) {
+            (Palette::Standard, MenuTone::Title) => Color::srgb(0.0, 0.7, 0.7),
+            (Palette::Standard, MenuTone::Alert) => Color::srgb_u8(0xAA, 0x22, 0x22),
+            (Palette::Standard, MenuTone::Accent) => Color::srgb_u8(0xF8, 0xE4, 0x73),
+            (Palette::RedGreen, MenuTone::Title) => Color::srgb_u8(0x56, 0xB4, 0xE9),
+            (Palette::RedGreen, MenuTone::Alert) => Color::srgb_u8(0xE6, 0x9F, 0x00),
+            (Palette::RedGreen, MenuTone::Accent) => Color::srgb_u8(0xF0, 0xE4, 0x42),
+            (Palette::BlueYellow, MenuTone::Title) => Color::srgb_u8(0x00, 0x9E, 0x73),
+            (Palette::BlueYellow, MenuTone::Alert) => Color::srgb_u8(0xD5, 0x5E, 0x00),
+            (Palette::BlueYellow, MenuTone::Accent) => Color::srgb_u8(0xCC, 0x79, 0xA7),
+        }
+    }
+    // Tint of the ship while invincible, see `ship_invincible_color`
+    pub fn invincible(&self) -> Color {
+        match self {
+            Palette::Standard => Color::srgb(1.0, 0.4, 0.2),
+            Palette::RedGreen => Color::srgb_u8(0x56, 0xB4, 0xE9),
+            Palette::BlueYellow => Color::srgb_u8(0xCC, 0x79, 0xA7),
+        }
+    }
+}
+
+// Role of a menu color, resolved with the selected `Palette`.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Default)]
+pub enum MenuTone {
+    #[default]
+    Title,
+    // Game over and failures
+    Alert,
+    // Pause and shop
+    Accent,
+}
+
+// Accessibility options, edited from their own screen opened from the Settings menu,
+// persisted in the save directory and applied live.
+#[derive(Debug, Resource, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Accessibility {
+    pub palette: Palette,
+    // Blinking texts and the flashing of the invincible ship, replaced by steady colors when off
+    pub flashing: bool,
+    // Dims the background behind the gameplay
+    pub high_contrast: bool,
+    // Relative speed of the game, one of `GAME_SPEEDS`
+    pub game_speed: f32,
+    pub large_text: bool,
+    // Off switch of the screen shake, hit-stop, zoom punches and damage vignette
+    pub camera_effects: bool,
+    // Intensities of the camera effects, from 0.0 to 1.0, see `CameraEffectMessage`
+    pub shake_intensity: f32,
+    pub zoom_intensity: f32,
+    pub vignette_intensity: f32,
+}
+impl Default for Accessibility {
+    fn default() -> Self {
+        Accessibility {
+            palette: Palette::default(),
+            flashing: true,
+            high_contrast: false,
+            game_speed: 1.0,
+            large_text: false,
+            camera_effects: true,
+            shake_intensity: 1.0,
+            zoom_intensity: 1.0,
+            vignette_intensity: 1.0,
+        }
+    }
+}
+impl Accessibility {
+    pub fn background_brightness(&self) -> f32 {
+        if self.high_contrast {
+            HIGH_CONTRAST_BACKGROUND
+        } else {
+            1.0
+        }
+    }
+}
+
+pub struct AccessibilityPlugin;
+
+impl Plugin for AccessibilityPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(load_save::<Accessibility>(ACCESSIBILITY_SAVE_FILE))
+            .add_systems(OnEnter(AppState::Accessibility), spawn_accessibility_menu)
+            .add_systems(
+                OnExit(AppState::Accessibility),
+                |accessibility: Res<Accessibility>| {
+                    write_save(ACCESSIBILITY_SAVE_FILE, &*accessibility);
+                },
+            )
+            .add_systems(
+                Update,
+                (apply_game_speed, apply_text_scale).run_if(resource_changed::<Accessibility>),
+            );
+    }
+}
+
+// Virtual time drives the fixed timestep, the whole simulation is slowed down.
+fn apply_game_speed(accessibility: Res<Accessibility>, mut time: ResMut<Time<Virtual>>) {
+    time.set_relative_speed(accessibility.game_speed);
+}
+
+fn apply_text_scale(accessibility: Res<Accessibility>, mut ui_scale: ResMut<UiScale>) {
+    ui_scale.0 = if accessibility.large_text {
+        LARGE_TEXT_SCALE
+    } else {
+        1.0
+    };
+}
+
+fn spawn_accessibility_menu(
+    mut commands: Commands,
+    assets: Res<UiAssets>,
+    accessibility: Res<Accessibility>,
+) {
+    let speeds = GAME_SPEEDS
+        .iter()
+        .map(|speed| format!("{:.0}%", speed * 100.0))
+        .collect::<Vec<_>>();
+    let entity = MenuHandler {
+        main_text: "menu-accessibility".into(),
+        main_text_color: MenuTone::Title,
+        main_text_blink: false,
+        selected_id: 0,
+        entries: vec![
+            MenuEntry::choice(
+                "accessibility-palette",
+                Palette::ALL
+                    .map(|palette| palette.key().to_string())
+                    .to_vec(),
+                Palette::ALL
+                    .iter()
+                    .position(|palette| *palette == accessibility.palette)
+                    .unwrap_or(0),
+                |world, index| world.resource_mut::<Accessibility>().palette = Palette::ALL[index],
+            ),
+            MenuEntry::toggle(
+                "accessibility-flashing",
+                accessibility.flashing,
+                |world, flashing| world.resource_mut::<Accessibility>().flashing = flashing,
+            ),
+            MenuEntry::toggle(
+                "accessibility-high-contrast",
+                accessibility.high_contrast,
+                |world, high_contrast| {
+                    world.resource_mut::<Accessibility>().high_contrast = high_contrast
+                },
+            ),
+            MenuEntry::choice(
+                "accessibility-game-speed",
+                speeds,
+                GAME_SPEEDS
+                    .iter()
+                    .position(|speed| *speed == accessibility.game_speed)
+                    .unwrap_or(GAME_SPEEDS.len() - 1),
+                |world, index| {
+                    world.resource_mut::<Accessibility>().game_speed = GAME_SPEEDS[index]
+                },
+            ),
+            MenuEntry::toggle(
+                "accessibility-large-text",
+                accessibility.large_text,
+                |world, large_text| world.resource_mut::<Accessibility>().large_text = large_text,
+            ),
+            MenuEntry::toggle(
+                "accessibility-camera-effects",
+                accessibility.camera_effects,
+                |world, enabled| world.resource_mut::<Accessibility>().camera_effects = enabled,
+            ),
+            MenuEntry::slider(
+                "accessibility-shake",
+                accessibility.shake_intensity,
+                (0.0, 1.0, 0.25),
+                |world, intensity| {
+                    world.resource_mut::<Accessibility>().shake_intensity = intensity
+                },
+            ),
+            MenuEntry::slider(
+                "accessibility-zoom",
+                accessibility.zoom_intensity,
+                (0.0, 1.0, 0.25),
+                |world, intensity| world.resource_mut::<Accessibility>().zoom_intensity = intensity,
+            ),
+            MenuEntry::slider(
+                "accessibility-vignette",
+                accessibility.vignette_intensity,
+                (0.0, 1.0, 0.25),
+                |world, intensity| {
+                    world.resource_mut::<Accessibility>().vignette_intensity = intensity
+                },
+            ),
+            MenuEntry::button("menu-back", MenuCommand::Back),
+        ],
+    }
+    .spawn(&mut commands, assets.font.clone());
+    commands
+        .entity(entity)
+        .insert(DespawnOnExit(AppState::Accessibility));
+}
@@ -0,0 +1,368 @@
+use std::collections::{BTreeMap, BTreeSet};
+
+use serde::{Deserialize, Serialize};
+
+use crate::prelude::*;
+
+pub const ACHIEVEMENTS_SAVE_FILE: &str = "achievements.ron";
+const TOAST_DURATION: f32 = 3.0;
+
+// Statistics tracked across runs, achievements are awarded on their values.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Serialize, Deserialize)]
+pub enum Counter {
+    // Total of asteroids destroyed by the player
+    AsteroidsDestroyed,
+    // Total of metal asteroids destroyed by the player
+    MetalDestroyed,
+    // Total of waves cleared without losing a life
+    FlawlessWaves,
+    // Highest wave reached in a run
+    BestWave,
+    // Highest score reached in a run
+    BestScore,
+}
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Serialize, Deserialize)]
+pub enum Achievement {
+    FirstBlood,
+    Destroyer,
+    Metalhead,
+    Untouchable,
+    Flawless,
+    Veteran,
+    HighScorer,
+}
+impl Achievement {
+    pub const ALL: [Achievement; 7] = [
+        Achievement::FirstBlood,
+        Achievement::Destroyer,
+        Achievement::Metalhead,
+        Achievement::Untouchable,
+        Achievement::Flawless,
+        Achievement::Veteran,
+        Achievement::HighScorer,
+    ];
+    // `Locale` key of the name, the description key adding a `-description` suffix
+    pub fn key(&self) -> &'static str {
+        match self {
+            Achievement::FirstBlood => "achievement-first-blood",
+            Achievement::Destroyer => "achievement-destroyer",
+            Achievement::Metalhead => "achievement-metalhead",
+            Achievement::Untouchable => "achievement-untouchable",
+            Achievement::Flawless => "achievement-flawless",
+            Achievement::Veteran => "achievement-veteran",
+            Achievement::HighScorer => "achievement-high-scorer",
+        }
+    }
+    pub fn description(&self) -> String {
+        format!("{}-description", self.key())
+    }
+    // The counter and the value it must reach
+    pub fn goal(&self) -> (Counter, u32) {
+        match self {
+            Achievement::FirstBlood => (Counter::AsteroidsDestroyed, 1),
+            Achievement::Destroyer => (Counter::AsteroidsDestroyed, 1000),
+            Achievement::Metalhead => (Counter::MetalDestroyed, 100),
+            Achievement::Untouchable => (Counter::FlawlessWaves, 1),
+            Achievement::Flawless => (Counter::FlawlessWaves, 25),
+            Achievement::Veteran => (Counter::BestWave, 10),
+            Achievement::HighScorer => (Counter::BestScore, 10000),
+        }
+    }
+}
+
+// Counters and unlocked achievements, persisted in the save directory.
+#[derive(Debug, Default, Resource, Serialize, Deserialize)]
+pub struct AchievementProgress {
+    pub counters: BTreeMap<Counter, u32>,
+    pub unlocked: BTreeSet<Achievement>,
+}
+impl AchievementProgress {
+    pub fn counter(&self, counter: Counter) -> u32 {
+        self.counters.get(&counter).copied().unwrap_or(0)
+    }
+    pub fn add(&mut self, counter: Counter, amount: u32) {
+        *self.counters.entry(counter).or_default() += amount;
+    }
+    pub fn record_max(&mut self, counter: Counter, value: u32) {
+        let entry = self.counters.entry(counter).or_default();
+        *entry = (*entry).max(value);
+    }
+    // Unlock the achievements whose goal has been reached, returns the newly unlocked ones.
+    pub fn unlock_reached(&mut self) -> Vec<Achievement> {
+        let reached = Achievement::ALL
+            .into_iter()
+            .filter(|achievement| {
+                let (counter, target) = achievement.goal();
+                !self.unlocked.contains(achievement) && self.counter(counter) >= target
+            })
+            .collect::<Vec<_>>();
+        self.unlocked.extend(reached.iter().copied());
+        reached
+    }
+}
+
+// Damage tracking of the current wave, for the flawless achievements.
+#[derive(Debug, Default, Resource)]
+pub struct WaveDamageTracker {
+    // Set when the ship is hit during the wave
+    pub damaged: bool,
+}
+
+#[derive(Message)]
+pub struct AchievementUnlockedMessage {
+    pub achievement: Achievement,
+}
+
+// Notification displayed for a few seconds, whatever the current screen.
+#[derive(Component)]
+pub struct Toast {
+    timer: Timer,
+}
+// Container stacking the toasts in the bottom right corner
+#[derive(Component)]
+pub struct ToastStack;
+
+pub struct AchievementPlugin;
+
+impl Plugin for AchievementPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(load_save::<AchievementProgress>(ACHIEVEMENTS_SAVE_FILE))
+            .add_message::<AchievementUnlockedMessage>()
+            .add_systems(OnEnter(AppState::Setup), spawn_toast_stack)
+            .add_systems(OnEnter(AppState::Achievements), spawn_achievements_menu)
+            .add_systems(OnEnter(GameState::Setup), |mut commands: Commands| {
+                commands.insert_resource(WaveDamageTracker::default());
+            })
+            .add_systems(OnEnter(GameState::Over), save_achievement_progress)
+            .add_systems(OnExit(AppState::Game), save_achievement_progress)
+            .add_systems(
+                Last,
+                save_achievement_progress.run_if(on_message::<AppExit>),
+            )
+            .add_systems(
+                FixedUpdate,
+                (
+                    wave_achievement_system,
+                    unlock_achievement_system.run_if(resource_changed::<AchievementProgress>),
+                )
+                    .chain()
+                    .run_if(in_state(GameState::Running)),
+            )
+            .add_systems(Update, (spawn_toast, animate_toast))
+            .add_observer(achievement_on_ship_damage)
+            .add_observer(achievement_on_asteroid_destroyed);
+    }
+}
+
+// Any hit on the ship counts, even absorbed by its shield or extra lives.
+fn achievement_on_ship_damage(
+    damage: On<Damage>,
+    tracker: Option<ResMut<WaveDamageTracker>>,
+    ships: Query<(), With<Ship>>,
+) {
+    if let Some(mut tracker) = tracker
+        && ships.contains(damage.entity)
+    {
+        tracker.damaged = true;
+    }
+}
+
+// Only the asteroids destroyed by a ship are counted.
+fn achievement_on_asteroid_destroyed(
+    destroyed: On<Destroyed>,
+    mut progress: ResMut<AchievementProgress>,
+    asteroids: Query<&Asteroid>,
+    ships: Query<(), With<Ship>>,
+) {
+    let Ok(asteroid) = asteroids.get(destroyed.entity) else {
+        return;
+    };
+    if destroyed
+        .source
+        .is_some_and(|source| ships.contains(source))
+    {
+        progress.add(Counter::AsteroidsDestroyed, 1);
+        if asteroid.material == AsteroidMaterial::Metal {
+            progress.add(Counter::MetalDestroyed, 1);
+        }
+    }
+}
+
+// A wave is flawless when the ship was not hit between its start and its clearing.
+fn wave_achievement_system(
+    arena: Res<Arena>,
+    mut tracker: ResMut<WaveDamageTracker>,
+    mut progress: ResMut<AchievementProgress>,
+    mut wave_started_events: MessageReader<WaveStartedMessage>,
+    mut wave_cleared_events: MessageReader<WaveClearedMessage>,
+) {
+    for _ in wave_cleared_events.read() {
+        if !tracker.damaged {
+            progress.add(Counter::FlawlessWaves, 1);
+        }
+    }
+    for event in wave_started_events.read() {
+        tracker.damaged = false;
+        progress.record_max(Counter::BestWave, event.wave);
+    }
+    if arena.score > progress.counter(Counter::BestScore) {
+        progress.record_max(Counter::BestScore, arena.score);
+    }
+}
+
+// Unlocks are saved straight away, counters at the end of a run, when leaving the game or quitting.
+fn unlock_achievement_system(
+    mut progress: ResMut<AchievementProgress>,
+    mut unlocked_events: MessageWriter<AchievementUnlockedMessage>,
+) {
+    let unlocked = progress.bypass_change_detection().unlock_reached();
+    if unlocked.is_empty() {
+        return;
+    }
+    for achievement in unlocked {
+        unlocked_events.write(AchievementUnlockedMessage { achievement });
+    }
+    write_save(ACHIEVEMENTS_SAVE_FILE, &*progress);
+}
+
+fn save_achievement_progress(progress: Res<AchievementProgress>) {
+    write_save(ACHIEVEMENTS_SAVE_FILE, &*progress);
+}
+
+fn spawn_toast_stack(mut commands: Commands) {
+    commands.spawn((
+        Node {
+            position_type: PositionType::Absolute,
+            right: Val::Px(20.0),
+            bottom: Val::Px(20.0),
+            flex_direction: FlexDirection::ColumnReverse,
+            align_items: AlignItems::FlexEnd,
+            ..default()
+        },
+        GlobalZIndex(10),
+        ToastStack,
+    ));
+}
+
+fn spawn_toast(
+    mut commands: Commands,
+    assets: Res<UiAssets>,
+    locale: Res<Locale>,
+    mut unlocked_events: MessageReader<AchievementUnlockedMessage>,
+    stack: Query<Entity, With<ToastStack>>,
+) {
+    let Ok(stack) = stack.single() else {
+        return;
+    };
+    for event in unlocked_events.read() {
+        commands.entity(stack).with_child((
+            Node {
+                padding: UiRect::all(Val::Px(10.0)),
+                margin: UiRect::all(Val::Px(5.0)),
+                border: UiRect::all(Val::Px(2.0)),
+                flex_direction: FlexDirection::Column,
+                ..default()
+            },
+            BorderColor::all(Color::srgb_u8(0xF8, 0xE4, 0x73)),
+            BorderRadius::all(Val::Px(10.0)),
+            BackgroundColor(Color::srgba(0.0, 0.0, 0.0, 0.8)),
+            Toast {
+                timer: Timer::from_seconds(TOAST_DURATION, TimerMode::Once),
+            },
+            // Slides in from the right border
+            UiTween::new(MENU_TWEEN_DURATION * 2.0).slide(Vec2::new(300.0, 0.0)),
+            children![
+                (
+                    Text::new(locale.format(
+                        "achievement-toast",
+                        &[("name", &locale.tr(event.achievement.key()))],
+                    )),
+                    TextFont {
+                        font: assets.font.clone(),
+                        font_size: 20.0,
+                        ..default()
+                    },
+                    TextColor(Color::srgb_u8(0xF8, 0xE4, 0x73)),
+                ),
+                (
+                    Text::new(locale.tr(&event.achievement.description())),
+                    TextFont {
+                        font: assets.font_fira.clone(),
+                        font_size: 16.0,
+                        ..default()
+                    },
+                    TextColor(Color::WHITE),
+                )
+            ],
+        ));
+    }
+}
+
+// Toasts use real time, they keep fading out while the game is paused.
+fn animate_toast(
+    mut commands: Commands,
+    time: Res<Time<Real>>,
+    mut toasts: Query<(Entity, &mut Toast, &mut BackgroundColor)>,
+) {
+    for (entity, mut toast, mut background) in toasts.iter_mut() {
+        toast.timer.tick(time.delta());
+        if toast.timer.is_finished() {
+            commands.entity(entity).despawn();
+        } else {
+            background
+                .0
+                .set_alpha(0.8 * (1.0 - toast.timer.fraction().powi(4)));
+        }
+    }
+}
+
+fn spawn_achievements_menu(
+    mut commands: Commands,
+    assets: Res<UiAssets>,
+    locale: Res<Locale>,
+    progress: Res<AchievementProgress>,
+) {
+    let mut entries = vec![];
+    for achievement in Achievement::ALL {
+        let (counter, target) = achievement.goal();
+        let unlocked = progress.unlocked.contains(&achievement);
+        let label = if unlocked {
+            format!("{} *", locale.tr(achievement.key()))
+        } else {
+            locale.tr(achievement.key())
+        };
+        let status = if unlocked {
+            locale.tr("achievement-unlocked")
+        } else {
+            format!("{}/{}", progress.counter(counter).min(target), target)
+        };
+        entries.push(MenuEntry::label(label).with_description(format!(
+            "{} - {}",
+            locale.tr(&achievement.description()),
+            status
+        )));
+    }
+    entries.push(
+        MenuEntry::button("menu-menu", MenuCommand::Open(AppState::Menu)).with_description(
+            locale.format(
+                "achievement-count",
+                &[
+                    ("unlocked", &progress.unlocked.len()),
+                    ("total", &Achievement::ALL.len()),
+                ],
+            ),
+        ),
+    );
+    let entity = MenuHandler {
+        main_text: "menu-trophies".into(),
+        main_text_color: MenuTone::Title,
+        main_text_blink: false,
+        selected_id: 0,
+        entries,
+    }
+    .spawn(&mut commands, assets.font.clone());
+    commands
+        .entity(entity)
+        .insert(DespawnOnExit(AppState::Achievements));
+}
@@ -1,3 +1,8 @@
+use bevy_transform_interpolation::TranslationEasingState;
+use rand::SeedableRng;
+use rand_chacha::ChaCha8Rng;
+use serde::{Deserialize, Serialize};
+
 use crate::prelude::*;
 
 pub const ARENA_WIDTH: f32 = 1280.0;
@@ -7,21 +12,137 @@ pub const ARENA_HEIGHT: f32 = 800.0;
 pub struct Arena {
     pub asteroid_spawn_timer: Timer,
     pub score: u32,
+    // Current wave number, starting at 1, 0 before the first wave starts
+    pub wave: u32,
+    // Asteroids of the current wave still to be spawned
+    pub asteroids_to_spawn: u32,
+    // Scripted waves of this arena, procedural endless waves follow them.
+    // When empty, asteroids spawn on an accelerating timer instead,
+    // in waves of `OPEN_ENDED_WAVE_ASTEROIDS`.
+    pub waves: Vec<WaveDefinition>,
+    // Asteroid entry points, a random arena edge is used when empty
+    pub asteroid_spawns: Vec<Vec2>,
+    // Current wave metal asteroid ratio and crystal drop chance, see `WaveDefinition`
+    pub metal_ratio: f32,
+    pub crystal_chance: f32,
+}
+impl Arena {
+    pub fn wave_definition(&self, wave: u32, rng: &mut impl Rng) -> WaveDefinition {
+        self.waves
+            .get(wave as usize - 1)
+            .cloned()
+            .unwrap_or_else(|| WaveDefinition::endless(wave, rng))
+    }
+    // Without wave data, waves end once their asteroids are spawned, see `arena_asteroids`.
+    pub fn is_open_ended(&self) -> bool {
+        self.waves.is_empty()
+    }
 }
 
+// Random generators of the gameplay, seeded per run so that a run can be replayed,
+// see `DailyChallenge`. Purely visual randomness does not need to use them.
+// ChaCha gives the same sequences on every platform and release, unlike `StdRng`.
+// Each stream is independent: destroying asteroids in another order, splitting them
+// or dropping crystals does not change the asteroids of the next waves.
+#[derive(Resource)]
+pub struct GameRng {
+    // Wave contents and asteroid arrivals
+    pub waves: ChaCha8Rng,
+    // Asteroid splits and crystal drops
+    pub drops: ChaCha8Rng,
+}
+impl GameRng {
+    pub fn new(seed: u64) -> Self {
+        let mut waves = ChaCha8Rng::seed_from_u64(seed);
+        waves.set_stream(0);
+        let mut drops = ChaCha8Rng::seed_from_u64(seed);
+        drops.set_stream(1);
+        GameRng { waves, drops }
+    }
+}
+
+// Seed of the `GameRng` of the current run, displayed on the game over screen.
+#[derive(Debug, Resource)]
+pub struct RunSeed(pub u64);
+
 #[derive(PhysicsLayer, Default)]
 pub enum GameLayer {
     #[default]
     Player,
     Laser,
     Asteroid,
+    Obstacle,
+    Pickup,
+}
+
+// The different ways an entity can be damaged.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Serialize, Deserialize)]
+pub enum DamageKind {
+    // Hit by a laser shot
+    Laser,
+    // Physical contact between two bodies, like the ship ramming an asteroid
+    Collision,
+    // Arena hazards, like the core of a gravity well
+    Hazard,
 }
+impl DamageKind {
+    // `Locale` key of the name
+    pub fn key(&self) -> &'static str {
+        match self {
+            DamageKind::Laser => "damage-laser",
+            DamageKind::Collision => "damage-collision",
+            DamageKind::Hazard => "damage-hazard",
+        }
+    }
+}
+
 //
 // An event that will be triggered whenever an entity receives damage.
-// This game is simple so there is no need for damage types, values or source types.
 #[derive(EntityEvent)]
 pub struct Damage {
     pub entity: Entity,
+    // Entity responsible for the damage, if any.
+    // For lasers this is the ship that fired it, not the laser itself.
+    pub source: Option<Entity>,
+    // Number of hit points removed from the entity `Health`
+    pub amount: u32,
+    pub kind: DamageKind,
+    // The laser dealing the damage, if any
+    pub projectile: Option<Entity>,
+    // World position where the damage has been received
+    pub contact: Vec2,
+}
+
+//
+// An event that will be triggered when an entity `Health` drops to 0.
+// The entity is still alive when observers run, its despawn is left
+// to its own observer, so that score, drops or VFX can react independently.
+#[derive(EntityEvent)]
+pub struct Destroyed {
+    pub entity: Entity,
+    // The source of the killing blow
+    pub source: Option<Entity>,
+    pub kind: DamageKind,
+    // World position of the destroyed entity
+    pub position: Vec2,
+}
+
+// Hit points of an entity that can receive `Damage`.
+#[derive(Component, Debug)]
+pub struct Health {
+    pub current: u32,
+    pub max: u32,
+}
+impl Health {
+    pub fn new(max: u32) -> Self {
+        Health { current: max, max }
+    }
+    // Remove `amount` hit points, returns true when this damage was the killing blow.
+    pub fn damage(&mut self, amount: u32) -> bool {
+        let was_alive = self.current > 0;
+        self.current = self.current.saturating_sub(amount);
+        was_alive && self.current == 0
+    }
 }
 
 pub struct ArenaPlugin;
@@ -41,22 +162,60 @@ impl Plugin for ArenaPlugin {
                     physics_time.pause();
                 },
             )
-            .add_systems(Update, movement.run_if(in_state(GameState::Running)));
+            .add_systems(
+                OnEnter(GameState::Shop),
+                |mut physics_time: ResMut<Time<Physics>>| {
+                    physics_time.pause();
+                },
+            )
+            .add_systems(FixedUpdate, movement.run_if(in_state(GameState::Running)));
     }
 }
 
-fn spawn_arena(mut commands: Commands) {
+fn spawn_arena(
+    mut commands: Commands,
+    selected_level: Res<SelectedLevel>,
+    mode: Res<SelectedMode>,
+    daily_challenge: Option<Res<DailyChallenge>>,
+) {
+    let (mut waves, asteroid_spawns) = match &selected_level.0 {
+        Some(level) => (level.waves.clone(), level.asteroid_spawns.clone()),
+        None => (vec![], vec![]),
+    };
+    if mode.0 == GameMode::ClearField {
+        waves = vec![clear_field_wave()];
+    }
     commands.insert_resource(Arena {
         asteroid_spawn_timer: Timer::from_seconds(5.0, TimerMode::Once),
         score: 0,
+        wave: 0,
+        asteroids_to_spawn: 0,
+        waves,
+        asteroid_spawns,
+        metal_ratio: 0.0,
+        crystal_chance: 0.0,
     });
 
-    // Physics configuration without gravity
+    let seed = match daily_challenge {
+        Some(challenge) => challenge.seed,
+        None => rand::random(),
+    };
+    commands.insert_resource(GameRng::new(seed));
+    commands.insert_resource(RunSeed(seed));
+
+    // Physics configuration without gravity.
+    // Attraction only comes from local hazards, see `GravityWell`.
     commands.insert_resource(Gravity::ZERO);
 }
 
-fn movement(mut query: Query<(&LinearVelocity, &mut Position)>) {
-    for (linvel, mut position) in query.iter_mut() {
+fn movement(
+    mut query: Query<(
+        &LinearVelocity,
+        &mut Position,
+        Option<&mut TranslationEasingState>,
+    )>,
+) {
+    for (linvel, mut position, easing) in query.iter_mut() {
         let mut x = position.x;
         let mut y = position.y;
         let mut updated = false;
@@ -80,6 +239,12 @@ fn movement(mut query: Query<(&LinearVelocity, &mut Position)>) {
         if updated {
             position.x = x;
             position.y = y;
+            // Restart the interpolation from the wrapped position,
+            // instead of easing the transform across the whole arena
+            if let Some(mut easing) = easing {
+                let z = easing.start.map_or(0.0, |start| start.z);
+                easing.start = Some(position.extend(z));
+            }
         }
     }
 }
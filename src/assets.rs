@@ -1,12 +1,13 @@
+use std::collections::HashMap;
+
 use crate::prelude::*;
 
 #[derive(Debug, Resource)]
 pub struct SpriteAssets {
-    pub laser: Handle<Image>,
+    pub lasers: HashMap<WeaponKind, Handle<Image>>,
     pub meteor_big: Handle<Image>,
     pub meteor_med: Handle<Image>,
     pub meteor_small: Handle<Image>,
-    pub player_ship: Handle<Image>,
     pub ship_explosion: Handle<Image>,
     pub ship_contact: Handle<Image>,
     pub asteroid_explosion: Handle<Image>,
@@ -14,6 +15,8 @@ pub struct SpriteAssets {
 #[derive(Debug, Resource)]
 pub struct AudioAssets {
     pub laser_trigger: Handle<AudioSource>,
+    // Synthesized trigger sounds, for weapons having one
+    pub weapon_triggers: HashMap<WeaponKind, Handle<LaserSound>>,
     pub ship_explosion: Handle<AudioSource>,
     pub ship_contact: Handle<AudioSource>,
     pub asteroid_explosion: Handle<AudioSource>,
@@ -25,6 +28,16 @@ pub struct UiAssets {
     pub font_fira: Handle<Font>,
     pub ship_life: Handle<Image>,
 }
+impl UiAssets {
+    // `kenvector_future.ttf` only has ASCII glyphs, other texts fall back to Fira Sans.
+    pub fn font_for(&self, text: &str) -> Handle<Font> {
+        if text.is_ascii() {
+            self.font.clone()
+        } else {
+            self.font_fira.clone()
+        }
+    }
+}
 
 pub struct AssetsPlugin;
 
@@ -34,19 +47,32 @@ impl Plugin for AssetsPlugin {
     }
 }
 
-fn setup(mut commands: Commands, asset_server: Res<AssetServer>) {
+fn setup(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    mut laser_sounds: ResMut<Assets<LaserSound>>,
+) {
     commands.insert_resource(SpriteAssets {
-        laser: asset_server.load("laserRed07.png"),
+        lasers: WeaponKind::ALL
+            .iter()
+            .map(|kind| (*kind, asset_server.load(kind.definition().sprite)))
+            .collect(),
         meteor_big: asset_server.load("meteorBrown_big1.png"),
         meteor_med: asset_server.load("meteorBrown_med1.png"),
         meteor_small: asset_server.load("meteorBrown_small1.png"),
-        player_ship: asset_server.load("playerShip2_red.png"),
         ship_explosion: asset_server.load("explosion01.png"),
         ship_contact: asset_server.load("explosion01.png"),
         asteroid_explosion: asset_server.load("flash00.png"),
     });
     commands.insert_resource(AudioAssets {
         laser_trigger: asset_server.load("sfx_laser1.ogg"),
+        weapon_triggers: WeaponKind::ALL
+            .iter()
+            .filter_map(|kind| {
+                let sound = kind.definition().sound?;
+                Some((*kind, laser_sounds.add(sound)))
+            })
+            .collect(),
         ship_explosion: asset_server.load("Explosion_ship.ogg"),
         ship_contact: asset_server.load("Explosion.ogg"),
         asteroid_explosion: asset_server.load("Explosion.ogg"),
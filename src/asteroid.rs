@@ -4,6 +4,7 @@ use core::time::Duration;
 #[derive(Message)]
 pub struct AsteroidSpawnMessage {
     pub size: AsteroidSize,
+    pub material: AsteroidMaterial,
     pub x: f32,
     pub y: f32,
     pub vx: f32,
@@ -32,6 +33,15 @@ impl AsteroidSize {
         }
     }
 
+    // Hit points of a rock asteroid of this size, bigger asteroids take more hits
+    pub fn health(&self) -> u32 {
+        match self {
+            AsteroidSize::Big => 3,
+            AsteroidSize::Medium => 2,
+            AsteroidSize::Small => 1,
+        }
+    }
+
     // Defines for each if the asteroid is split on destruction
     // And the spawned sub-asteroid size and radius of spawning.
     pub fn split(&self) -> Option<(AsteroidSize, f32)> {
@@ -42,9 +52,38 @@ impl AsteroidSize {
         }
     }
 }
+// Metal asteroids are tougher, and worth more points.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Default)]
+pub enum AsteroidMaterial {
+    #[default]
+    Rock,
+    Metal,
+}
+impl AsteroidMaterial {
+    // Multiplier applied to the size health and score
+    pub fn factor(&self) -> u32 {
+        match self {
+            AsteroidMaterial::Rock => 1,
+            AsteroidMaterial::Metal => 3,
+        }
+    }
+    pub fn color(&self) -> Color {
+        match self {
+            AsteroidMaterial::Rock => Color::WHITE,
+            AsteroidMaterial::Metal => Color::srgb(0.6, 0.7, 0.9),
+        }
+    }
+}
+
 #[derive(Component)]
 pub struct Asteroid {
     pub size: AsteroidSize,
+    pub material: AsteroidMaterial,
+}
+impl Asteroid {
+    pub fn score(&self) -> u32 {
+        self.size.score() * self.material.factor()
+    }
 }
 
 pub struct AsteroidPlugin;
@@ -52,7 +91,7 @@ pub struct AsteroidPlugin;
 impl Plugin for AsteroidPlugin {
     fn build(&self, app: &mut App) {
         app.add_message::<AsteroidSpawnMessage>().add_systems(
-            Update,
+            FixedUpdate,
             (arena_asteroids, spawn_asteroid_event).run_if(in_state(GameState::Running)),
         );
     }
@@ -62,6 +101,7 @@ fn spawn_asteroid_event(
     mut commands: Commands,
     mut event_reader: MessageReader<AsteroidSpawnMessage>,
     handles: Res<SpriteAssets>,
+    settings: Res<Settings>,
 ) {
     for event in event_reader.read() {
         let (sprite_handle, radius) = match event.size {
@@ -69,49 +109,81 @@ fn spawn_asteroid_event(
             AsteroidSize::Medium => (handles.meteor_med.clone(), 43. / 2.0),
             AsteroidSize::Small => (handles.meteor_small.clone(), 28. / 2.0),
         };
-        commands
-            .spawn((
-                Name::new(format!("Asteroid {}", event.size)),
-                Sprite {
-                    image: sprite_handle.clone(),
-                    ..default()
-                },
-                Transform::from_translation(Vec3::new(event.x, event.y, 1.0)),
-                Asteroid { size: event.size },
-                DespawnOnExit(AppState::Game),
-                CollisionLayers::new(
+        let mut asteroid = commands.spawn((
+            Name::new(format!("Asteroid {} {:?}", event.size, event.material)),
+            Transform::from_translation(Vec3::new(event.x, event.y, 1.0)),
+            Asteroid {
+                size: event.size,
+                material: event.material,
+            },
+            Health::new(event.size.health() * event.material.factor()),
+            DespawnOnExit(AppState::Game),
+            CollisionLayers::new(
+                GameLayer::Asteroid,
+                [
                     GameLayer::Asteroid,
-                    [GameLayer::Asteroid, GameLayer::Player, GameLayer::Laser],
-                ),
-                RigidBody::Dynamic,
-                Collider::circle(radius),
-                Restitution::new(0.5),
-                LinearVelocity(Vec2::new(event.vx, event.vy)),
-                AngularVelocity(event.angvel),
-            ))
-            .observe(on_asteroid_damage);
+                    GameLayer::Player,
+                    GameLayer::Laser,
+                    GameLayer::Obstacle,
+                ],
+            ),
+            RigidBody::Dynamic,
+            Collider::circle(radius),
+            Restitution::new(0.5),
+            LinearVelocity(Vec2::new(event.vx, event.vy)),
+            AngularVelocity(event.angvel),
+        ));
+        match settings.render_style {
+            RenderStyle::Sprite => asteroid.insert(Sprite {
+                image: sprite_handle.clone(),
+                color: event.material.color(),
+                ..default()
+            }),
+            RenderStyle::Vector => asteroid.insert(VectorShape {
+                color: event.material.color(),
+                ..VectorShape::asteroid(radius)
+            }),
+        };
+        asteroid
+            .observe(on_asteroid_damage)
+            .observe(on_asteroid_destroyed);
     }
 }
 
 fn arena_asteroids(
     time: Res<Time>,
     mut arena: ResMut<Arena>,
+    mut rng: ResMut<GameRng>,
     mut asteroid_spawn_events: MessageWriter<AsteroidSpawnMessage>,
     asteroids: Query<&Asteroid>,
 ) {
     arena.asteroid_spawn_timer.tick(time.delta());
-    if arena.asteroid_spawn_timer.is_finished() {
+    // Asteroids of the current wave are spawned one by one, on the spawn timer.
+    // Without wave data, they keep coming faster and faster.
+    if arena.asteroid_spawn_timer.is_finished() && arena.asteroids_to_spawn > 0 {
         arena.asteroid_spawn_timer.reset();
         let n_asteroid = asteroids.iter().count();
         if n_asteroid < 20 {
-            let duration = Duration::from_secs_f32(
-                (0.8 * arena.asteroid_spawn_timer.duration().as_secs_f32()).max(0.1),
-            );
-            arena.asteroid_spawn_timer.set_duration(duration);
-            let mut rng = thread_rng();
-            // 0: Top , 1:Left
-            let side = rng.gen_range(0..2u8);
+            if arena.is_open_ended() {
+                let duration = Duration::from_secs_f32(
+                    (0.8 * arena.asteroid_spawn_timer.duration().as_secs_f32()).max(0.1),
+                );
+                arena.asteroid_spawn_timer.set_duration(duration);
+            }
+            arena.asteroids_to_spawn -= 1;
+            let rng = &mut rng.waves;
+            // 0: Top , 1:Left, 2: Level spawn point
+            let side = if arena.asteroid_spawns.is_empty() {
+                rng.gen_range(0..2u8)
+            } else {
+                2
+            };
             let (x, y) = match side {
+                2 => {
+                    let spawn =
+                        arena.asteroid_spawns[rng.gen_range(0..arena.asteroid_spawns.len())];
+                    (spawn.x, spawn.y)
+                }
                 0 => (
                     rng.gen_range((-ARENA_WIDTH / 2.0)..(ARENA_WIDTH / 2.0)),
                     ARENA_HEIGHT / 2.0,
@@ -124,8 +196,14 @@ fn arena_asteroids(
             let vx = rng.gen_range((-ARENA_WIDTH / 4.0)..(ARENA_WIDTH / 4.0));
             let vy = rng.gen_range((-ARENA_HEIGHT / 4.0)..(ARENA_HEIGHT / 4.0));
             let angvel = rng.gen_range(-10.0..10.0);
+            let material = if rng.gen_bool(arena.metal_ratio as f64) {
+                AsteroidMaterial::Metal
+            } else {
+                AsteroidMaterial::Rock
+            };
             asteroid_spawn_events.write(AsteroidSpawnMessage {
                 size: AsteroidSize::Big,
+                material,
                 x,
                 y,
                 vx,
@@ -139,35 +217,56 @@ fn arena_asteroids(
 fn on_asteroid_damage(
     damage: On<Damage>,
     mut commands: Commands,
-    mut arena: ResMut<Arena>,
+    mut asteroids: Query<(&mut Health, &Transform)>,
+) {
+    let asteroid_entity = damage.entity;
+    let (mut health, asteroid_transform) = asteroids
+        .get_mut(asteroid_entity)
+        .expect("Missing Health and Transform on asteroid damage trigger");
+    if health.damage(damage.amount) {
+        commands.trigger(Destroyed {
+            entity: asteroid_entity,
+            source: damage.source,
+            kind: damage.kind,
+            position: asteroid_transform.translation.truncate(),
+        });
+    }
+}
+
+// A destroyed asteroid is split in smaller asteroids of the same material, if not already the smallest.
+fn on_asteroid_destroyed(
+    destroyed: On<Destroyed>,
+    mut commands: Commands,
     mut asteroid_spawn_events: MessageWriter<AsteroidSpawnMessage>,
+    mut rng: ResMut<GameRng>,
     asteroids: Query<(&Asteroid, &Transform, &AngularVelocity)>,
 ) {
-    let asteroid_entity = damage.entity;
-    let (asteroid, asteroid_transform, asteroid_angvel) = asteroids.get(asteroid_entity).unwrap();
-    arena.score += asteroid.size.score();
+    let asteroid_entity = destroyed.entity;
+    let (asteroid, asteroid_transform, asteroid_angvel) = asteroids
+        .get(asteroid_entity)
+        .expect("Missing Asteroid on destroyed trigger");
+    // Asteroids swallowed by a hazard vanish whole
+    if destroyed.kind != DamageKind::Hazard
+        && let Some((size, radius)) = asteroid.size.split()
     {
-        if let Some((size, radius)) = asteroid.size.split() {
-            let mut rng = thread_rng();
-            for i in 0..4 {
-                //rng.gen_range(1..4u8) {
-                let x_pos = if i % 2 == 0 { 1. } else { -1. };
-                let y_pos = if (i / 2) % 2 == 0 { 1. } else { -1. };
-                let x = asteroid_transform.translation.x + x_pos * 1.5 * radius;
-                let y = asteroid_transform.translation.y + y_pos * 1.5 * radius;
-                let vx =
-                    rng.gen_range((-ARENA_WIDTH / (radius / 4.))..(ARENA_WIDTH / (radius / 4.)));
-                let vy =
-                    rng.gen_range((-ARENA_HEIGHT / (radius / 4.))..(ARENA_HEIGHT / (radius / 4.)));
-                asteroid_spawn_events.write(AsteroidSpawnMessage {
-                    size,
-                    x,
-                    y,
-                    vx,
-                    vy,
-                    angvel: asteroid_angvel.0,
-                });
-            }
+        let rng = &mut rng.drops;
+        for i in 0..4 {
+            //rng.gen_range(1..4u8) {
+            let x_pos = if i % 2 == 0 { 1. } else { -1. };
+            let y_pos = if (i / 2) % 2 == 0 { 1. } else { -1. };
+            let x = asteroid_transform.translation.x + x_pos * 1.5 * radius;
+            let y = asteroid_transform.translation.y + y_pos * 1.5 * radius;
+            let vx = rng.gen_range((-ARENA_WIDTH / (radius / 4.))..(ARENA_WIDTH / (radius / 4.)));
+            let vy = rng.gen_range((-ARENA_HEIGHT / (radius / 4.))..(ARENA_HEIGHT / (radius / 4.)));
+            asteroid_spawn_events.write(AsteroidSpawnMessage {
+                size,
+                material: asteroid.material,
+                x,
+                y,
+                vx,
+                vy,
+                angvel: asteroid_angvel.0,
+            });
         }
     }
     commands.entity(asteroid_entity).despawn();
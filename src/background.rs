@@ -11,7 +11,14 @@ impl Plugin for BackgroundPlugin {
     fn build(&self, app: &mut App) {
         app.add_plugins(Material2dPlugin::<BackgroundMaterial>::default())
             .add_systems(OnEnter(AppState::Setup), spawn_background)
-            .add_systems(Update, update_background_time);
+            .add_systems(Update, update_background_time)
+            .add_systems(
+                Update,
+                (
+                    apply_background_render_style.run_if(resource_changed::<Settings>),
+                    apply_background_contrast.run_if(resource_changed::<Accessibility>),
+                ),
+            );
     }
 }
 
@@ -24,14 +31,42 @@ fn spawn_background(
     commands.spawn((
         Mesh2d(meshes.add(Rectangle::default())),
         Transform::from_scale(Vec3::new(ARENA_WIDTH, ARENA_HEIGHT, 1.0)),
-        MeshMaterial2d(materials.add(BackgroundMaterial { time: 0.0 })),
+        MeshMaterial2d(materials.add(BackgroundMaterial {
+            time: 0.0,
+            brightness: 1.0,
+        })),
     ));
 }
 
+// The retro vector style is drawn on a plain black background.
+fn apply_background_render_style(
+    settings: Res<Settings>,
+    mut backgrounds: Query<&mut Visibility, With<MeshMaterial2d<BackgroundMaterial>>>,
+) {
+    for mut visibility in backgrounds.iter_mut() {
+        *visibility = match settings.render_style {
+            RenderStyle::Sprite => Visibility::Inherited,
+            RenderStyle::Vector => Visibility::Hidden,
+        };
+    }
+}
+
+// High contrast mode dims the stars behind the gameplay.
+fn apply_background_contrast(
+    accessibility: Res<Accessibility>,
+    mut backgrounds: ResMut<Assets<BackgroundMaterial>>,
+) {
+    for (_, background) in backgrounds.iter_mut() {
+        background.brightness = accessibility.background_brightness();
+    }
+}
+
 #[derive(Asset, AsBindGroup, Debug, Clone, TypePath)]
 struct BackgroundMaterial {
     #[uniform(0)]
     time: f32,
+    #[uniform(1)]
+    brightness: f32,
 }
 
 impl Material2d for BackgroundMaterial {
@@ -0,0 +1,203 @@
+use crate::prelude::*;
+
+// Offset and rotation of the camera at full trauma
+const MAX_SHAKE_OFFSET: f32 = 24.0;
+const MAX_SHAKE_ANGLE: f32 = 0.05;
+// Trauma lost per second, a full shake lasts a bit more than a second
+const TRAUMA_DECAY: f32 = 0.9;
+// Zoom and vignette lost per second
+const ZOOM_DECAY: f32 = 0.6;
+const VIGNETTE_DECAY: f32 = 1.5;
+// Relative speed of the game during a hit-stop, nearly frozen
+const HIT_STOP_SPEED: f32 = 0.05;
+
+// Requests of the gameplay for a camera effect, scaled by the `Accessibility` intensities.
+#[derive(Message, Debug, Copy, Clone)]
+pub enum CameraEffectMessage {
+    // Trauma added, from 0.0 to 1.0, the shake growing with its square
+    Shake(f32),
+    // Seconds of real time the game is nearly frozen
+    HitStop(f32),
+    // Fraction of zoom in, easing back out
+    ZoomPunch(f32),
+    // Opacity of the red border, fading out
+    Vignette(f32),
+}
+
+// Current state of the effects, decaying with real time, they continue while the game is paused.
+#[derive(Debug, Default, Resource)]
+pub struct CameraEffects {
+    pub trauma: f32,
+    pub zoom: f32,
+    pub vignette: f32,
+    // Seconds left of the current hit-stop
+    pub hit_stop: f32,
+}
+
+// Full screen overlay drawing the damage vignette
+#[derive(Component)]
+pub struct DamageVignette;
+
+pub struct CameraEffectsPlugin;
+
+impl Plugin for CameraEffectsPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<CameraEffects>()
+            .add_message::<CameraEffectMessage>()
+            .add_systems(OnEnter(AppState::Setup), spawn_damage_vignette)
+            .add_systems(OnExit(AppState::Game), reset_camera_effects)
+            .add_systems(
+                Update,
+                (
+                    camera_effect_message_system,
+                    hit_stop_system,
+                    camera_shake_system,
+                    damage_vignette_system,
+                )
+                    .chain(),
+            );
+    }
+}
+
+fn spawn_damage_vignette(mut commands: Commands) {
+    commands.spawn((
+        Node {
+            position_type: PositionType::Absolute,
+            width: Val::Percent(100.0),
+            height: Val::Percent(100.0),
+            ..default()
+        },
+        BackgroundGradient::default(),
+        Visibility::Hidden,
+        GlobalZIndex(5),
+        DamageVignette,
+    ));
+}
+
+// Effects are ignored when disabled in the accessibility options.
+fn camera_effect_message_system(
+    accessibility: Res<Accessibility>,
+    mut effects: ResMut<CameraEffects>,
+    mut time: ResMut<Time<Virtual>>,
+    mut messages: MessageReader<CameraEffectMessage>,
+) {
+    if !accessibility.camera_effects {
+        messages.clear();
+        return;
+    }
+    for message in messages.read() {
+        match *message {
+            CameraEffectMessage::Shake(trauma) => {
+                effects.trauma = (effects.trauma + trauma * accessibility.shake_intensity).min(1.0);
+            }
+            CameraEffectMessage::HitStop(secs) => {
+                effects.hit_stop = effects.hit_stop.max(secs);
+                time.set_relative_speed(accessibility.game_speed * HIT_STOP_SPEED);
+            }
+            CameraEffectMessage::ZoomPunch(zoom) => {
+                effects.zoom = effects.zoom.max(zoom * accessibility.zoom_intensity);
+            }
+            CameraEffectMessage::Vignette(opacity) => {
+                effects.vignette = effects
+                    .vignette
+                    .max(opacity * accessibility.vignette_intensity);
+            }
+        }
+    }
+}
+
+// The game speed of the accessibility options is restored at the end of the hit-stop.
+fn hit_stop_system(
+    real_time: Res<Time<Real>>,
+    accessibility: Res<Accessibility>,
+    mut effects: ResMut<CameraEffects>,
+    mut time: ResMut<Time<Virtual>>,
+) {
+    if effects.hit_stop <= 0.0 {
+        return;
+    }
+    effects.hit_stop -= real_time.delta_secs();
+    if effects.hit_stop <= 0.0 {
+        effects.hit_stop = 0.0;
+        time.set_relative_speed(accessibility.game_speed);
+    }
+}
+
+// Trauma based shake, the camera is offset and rotated randomly, more with more trauma.
+// Zoom punches scale the projection down, easing back to the whole arena.
+fn camera_shake_system(
+    time: Res<Time<Real>>,
+    mut effects: ResMut<CameraEffects>,
+    mut cameras: Query<(&mut Transform, &mut Projection), With<Camera2d>>,
+) {
+    if effects.trauma == 0.0 && effects.zoom == 0.0 {
+        return;
+    }
+    let delta = time.delta_secs();
+    effects.trauma = (effects.trauma - TRAUMA_DECAY * delta).max(0.0);
+    effects.zoom = (effects.zoom - ZOOM_DECAY * delta).max(0.0);
+    let shake = effects.trauma * effects.trauma;
+    let mut rng = thread_rng();
+    for (mut transform, mut projection) in cameras.iter_mut() {
+        transform.translation.x = MAX_SHAKE_OFFSET * shake * rng.gen_range(-1.0..1.0);
+        transform.translation.y = MAX_SHAKE_OFFSET * shake * rng.gen_range(-1.0..1.0);
+        transform.rotation =
+            Quat::from_rotation_z(MAX_SHAKE_ANGLE * shake * rng.gen_range(-1.0..1.0));
+        if let Projection::Orthographic(orthographic) = &mut *projection {
+            orthographic.scale = 1.0 - EaseFunction::QuadraticOut.sample_clamped(effects.zoom);
+        }
+    }
+}
+
+// Red border, in the alert color of the palette, fading out.
+fn damage_vignette_system(
+    time: Res<Time<Real>>,
+    accessibility: Res<Accessibility>,
+    mut effects: ResMut<CameraEffects>,
+    mut vignettes: Query<(&mut Visibility, &mut BackgroundGradient), With<DamageVignette>>,
+) {
+    if effects.vignette == 0.0 {
+        return;
+    }
+    effects.vignette = (effects.vignette - VIGNETTE_DECAY * time.delta_secs()).max(0.0);
+    let color = accessibility
+        .palette
+        .color(MenuTone::Alert)
+        .with_alpha(effects.vignette);
+    for (mut visibility, mut gradient) in vignettes.iter_mut() {
+        *visibility = if effects.vignette > 0.0 {
+            Visibility::Inherited
+        } else {
+            Visibility::Hidden
+        };
+        *gradient = BackgroundGradient::from(RadialGradient::new(
+            UiPosition::CENTER,
+            RadialGradientShape::FarthestCorner,
+            vec![
+                ColorStop::new(Color::NONE, Val::Percent(50.0)),
+                ColorStop::new(color, Val::Percent(100.0)),
+            ],
+        ));
+    }
+}
+
+// Leaving the game stops every effect, the menus are drawn with a still camera.
+fn reset_camera_effects(
+    accessibility: Res<Accessibility>,
+    mut effects: ResMut<CameraEffects>,
+    mut time: ResMut<Time<Virtual>>,
+    mut cameras: Query<(&mut Transform, &mut Projection), With<Camera2d>>,
+    mut vignettes: Query<&mut Visibility, With<DamageVignette>>,
+) {
+    *effects = CameraEffects::default();
+    time.set_relative_speed(accessibility.game_speed);
+    for (mut transform, mut projection) in cameras.iter_mut() {
+        *transform = Transform::default();
+        if let Projection::Orthographic(orthographic) = &mut *projection {
+            orthographic.scale = 1.0;
+        }
+    }
+    for mut visibility in vignettes.iter_mut() {
+        *visibility = Visibility::Hidden;
+    }
+}
@@ -0,0 +1,509 @@
+use serde::{Deserialize, Serialize};
+
+use crate::prelude::*;
+
+pub const CAMPAIGN_SAVE_FILE: &str = "campaign.ron";
+pub const MAX_STARS: u32 = 3;
+
+// What the player has to achieve to complete a mission.
+#[derive(Debug, Copy, Clone)]
+pub enum Objective {
+    // Stay alive for the given duration
+    Survive { seconds: f32 },
+    // Destroy metal asteroids, split pieces included
+    DestroyMetal { count: u32 },
+    // Protect a freighter flying between two points
+    Escort { from: Vec2, to: Vec2 },
+    // Collect crystals dropped by destroyed asteroids
+    CollectCrystals { count: u32 },
+}
+
+// What earns the second star of a mission, on top of completing it.
+#[derive(Debug, Copy, Clone)]
+pub enum StarBonus {
+    // Complete the mission within this time, in seconds
+    ParTime { seconds: f32 },
+    // Destroy asteroids while completing the mission, for missions with a fixed duration
+    Destroyed { count: u32 },
+}
+
+pub struct MissionDefinition {
+    // `Locale` keys of the name and briefing
+    pub name: &'static str,
+    pub briefing: &'static str,
+    // Level file the mission is played in
+    pub level: &'static str,
+    pub objective: Objective,
+    pub bonus: StarBonus,
+}
+
+// Missions of the campaign, each one unlocked by completing the previous one.
+pub const MISSIONS: [MissionDefinition; 5] = [
+    MissionDefinition {
+        name: "mission-first-contact",
+        briefing: "mission-first-contact-briefing",
+        level: "levels/asteroid_belt.level.ron",
+        objective: Objective::Survive { seconds: 60.0 },
+        bonus: StarBonus::Destroyed { count: 30 },
+    },
+    MissionDefinition {
+        name: "mission-scrap-metal",
+        briefing: "mission-scrap-metal-briefing",
+        level: "levels/mission_scrap.level.ron",
+        objective: Objective::DestroyMetal { count: 12 },
+        bonus: StarBonus::ParTime { seconds: 120.0 },
+    },
+    MissionDefinition {
+        name: "mission-crystal-rush",
+        briefing: "mission-crystal-rush-briefing",
+        level: "levels/mission_crystals.level.ron",
+        objective: Objective::CollectCrystals { count: 6 },
+        bonus: StarBonus::ParTime { seconds: 150.0 },
+    },
+    MissionDefinition {
+        name: "mission-safe-passage",
+        briefing: "mission-safe-passage-briefing",
+        level: "levels/mission_escort.level.ron",
+        objective: Objective::Escort {
+            from: Vec2::new(-560.0, -200.0),
+            to: Vec2::new(560.0, -200.0),
+        },
+        bonus: StarBonus::ParTime { seconds: 45.0 },
+    },
+    MissionDefinition {
+        name: "mission-minefield",
+        briefing: "mission-minefield-briefing",
+        level: "levels/minefield.level.ron",
+        objective: Objective::Survive { seconds: 90.0 },
+        bonus: StarBonus::Destroyed { count: 40 },
+    },
+];
+
+// Best star rating of each mission, persisted in the save directory.
+#[derive(Debug, Default, Resource, Serialize, Deserialize)]
+pub struct CampaignProgress {
+    pub stars: Vec<u32>,
+}
+impl CampaignProgress {
+    pub fn stars(&self, mission: usize) -> u32 {
+        self.stars.get(mission).copied().unwrap_or(0)
+    }
+    // A mission is unlocked once the previous one has been completed.
+    pub fn is_unlocked(&self, mission: usize) -> bool {
+        mission == 0 || self.stars(mission - 1) > 0
+    }
+    // Keep the best rating, returns true when it has been improved.
+    pub fn record(&mut self, mission: usize, stars: u32) -> bool {
+        if self.stars.len() <= mission {
+            self.stars.resize(mission + 1, 0);
+        }
+        let improved = stars > self.stars[mission];
+        self.stars[mission] = self.stars[mission].max(stars);
+        improved
+    }
+}
+
+// Present while playing the campaign, from the mission selection to its results.
+#[derive(Debug, Resource)]
+pub struct ActiveMission {
+    pub index: usize,
+}
+impl ActiveMission {
+    pub fn definition(&self) -> &'static MissionDefinition {
+        &MISSIONS[self.index]
+    }
+}
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Default)]
+pub enum MissionStatus {
+    #[default]
+    InProgress,
+    Success,
+    Failed,
+}
+
+// Progress of the running mission toward its objective.
+#[derive(Debug, Default, Resource)]
+pub struct ObjectiveTracker {
+    pub elapsed: f32,
+    // Asteroids destroyed by the player, split pieces included
+    pub destroyed: u32,
+    pub metal_destroyed: u32,
+    pub crystals_collected: u32,
+    // Fraction of the freighter route already flown
+    pub escort_progress: f32,
+    // Set when the ship is hit, losing the flawless star
+    pub damaged: bool,
+    pub status: MissionStatus,
+    pub stars: u32,
+}
+impl ObjectiveTracker {
+    pub fn description(&self, objective: &Objective, locale: &Locale) -> String {
+        match objective {
+            Objective::Survive { seconds } => {
+                let seconds = format!("{:.0}", (seconds - self.elapsed).max(0.0));
+                locale.format("objective-survive", &[("seconds", &seconds)])
+            }
+            Objective::DestroyMetal { count } => locale.format(
+                "objective-metal",
+                &[
+                    ("destroyed", &self.metal_destroyed.min(*count)),
+                    ("count", count),
+                ],
+            ),
+            Objective::Escort { .. } => {
+                let progress = format!("{:.0}", self.escort_progress * 100.0);
+                locale.format("objective-escort", &[("progress", &progress)])
+            }
+            Objective::CollectCrystals { count } => locale.format(
+                "objective-crystals",
+                &[
+                    ("collected", &self.crystals_collected.min(*count)),
+                    ("count", count),
+                ],
+            ),
+        }
+    }
+    pub fn has_bonus(&self, bonus: &StarBonus) -> bool {
+        match bonus {
+            StarBonus::ParTime { seconds } => self.elapsed <= *seconds,
+            StarBonus::Destroyed { count } => self.destroyed >= *count,
+        }
+    }
+    pub fn is_complete(&self, objective: &Objective) -> bool {
+        match objective {
+            Objective::Survive { seconds } => self.elapsed >= *seconds,
+            Objective::DestroyMetal { count } => self.metal_destroyed >= *count,
+            Objective::Escort { .. } => self.escort_progress >= 1.0,
+            Objective::CollectCrystals { count } => self.crystals_collected >= *count,
+        }
+    }
+}
+
+// Handles of the mission levels, in `MISSIONS` order
+#[derive(Debug, Resource)]
+pub struct CampaignAssets {
+    pub levels: Vec<Handle<Level>>,
+}
+
+#[derive(Component)]
+pub struct UiObjective;
+
+pub struct CampaignPlugin;
+
+impl Plugin for CampaignPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(load_save::<CampaignProgress>(CAMPAIGN_SAVE_FILE))
+            .add_observer(mission_on_destroyed)
+            .add_observer(mission_on_damage)
+            .add_systems(OnEnter(AppState::Setup), load_campaign_levels)
+            .add_systems(OnEnter(AppState::Menu), |mut commands: Commands| {
+                commands.remove_resource::<ActiveMission>();
+                commands.remove_resource::<ObjectiveTracker>();
+            })
+            .add_systems(OnEnter(AppState::Campaign), spawn_campaign_menu)
+            .add_systems(OnEnter(AppState::Briefing), spawn_briefing_menu)
+            .add_systems(
+                OnEnter(GameState::Setup),
+                setup_mission.run_if(resource_exists::<ActiveMission>),
+            )
+            .add_systems(
+                OnEnter(GameState::Over),
+                spawn_mission_results.run_if(resource_exists::<ActiveMission>),
+            )
+            .add_systems(
+                FixedUpdate,
+                objective_tracker_system.run_if(
+                    in_state(GameState::Running)
+                        .and(resource_exists::<ActiveMission>)
+                        .and(resource_exists::<ObjectiveTracker>),
+                ),
+            )
+            .add_systems(
+                Update,
+                objective_hud_system.run_if(resource_exists::<ObjectiveTracker>),
+            );
+    }
+}
+
+fn load_campaign_levels(mut commands: Commands, asset_server: Res<AssetServer>) {
+    commands.insert_resource(CampaignAssets {
+        levels: MISSIONS
+            .iter()
+            .map(|mission| asset_server.load(mission.level))
+            .collect(),
+    });
+}
+
+fn star_text(stars: u32) -> String {
+    format!(
+        "{}{}",
+        "*".repeat(stars as usize),
+        "-".repeat((MAX_STARS - stars) as usize)
+    )
+}
+
+fn spawn_campaign_menu(
+    mut commands: Commands,
+    assets: Res<UiAssets>,
+    locale: Res<Locale>,
+    progress: Res<CampaignProgress>,
+) {
+    let mut entries = MISSIONS
+        .iter()
+        .enumerate()
+        .map(|(index, mission)| {
+            if progress.is_unlocked(index) {
+                MenuEntry::button(
+                    format!(
+                        "{} {}",
+                        locale.tr(mission.name),
+                        star_text(progress.stars(index))
+                    ),
+                    MenuCommand::run(move |world| world.insert_resource(ActiveMission { index }))
+                        .then(MenuCommand::Open(AppState::Briefing)),
+                )
+            } else {
+                MenuEntry::label("menu-locked").enabled(false)
+            }
+        })
+        .collect::<Vec<_>>();
+    entries.push(MenuEntry::button(
+        "menu-menu",
+        MenuCommand::Open(AppState::Menu),
+    ));
+    let entity = MenuHandler {
+        main_text: "menu-campaign".into(),
+        main_text_color: MenuTone::Title,
+        main_text_blink: false,
+        selected_id: 0,
+        entries,
+    }
+    .spawn(&mut commands, assets.font.clone());
+    commands
+        .entity(entity)
+        .insert(DespawnOnExit(AppState::Campaign));
+}
+
+fn spawn_briefing_menu(
+    mut commands: Commands,
+    assets: Res<UiAssets>,
+    locale: Res<Locale>,
+    mission: Res<ActiveMission>,
+    campaign_assets: Res<CampaignAssets>,
+    levels: Res<Assets<Level>>,
+) {
+    let definition = mission.definition();
+    let level = levels.get(&campaign_assets.levels[mission.index]).cloned();
+    let start =
+        MenuCommand::run(move |world| world.resource_mut::<SelectedLevel>().0 = level.clone())
+            .then(MenuCommand::Open(AppState::Game));
+    let entity = MenuHandler {
+        main_text: definition.name.into(),
+        main_text_color: MenuTone::Title,
+        main_text_blink: false,
+        selected_id: 0,
+        entries: vec![
+            MenuEntry::button("menu-start", start),
+            MenuEntry::button("menu-back", MenuCommand::Open(AppState::Campaign)),
+        ],
+    }
+    .spawn(&mut commands, assets.font.clone());
+    commands
+        .entity(entity)
+        .insert(DespawnOnExit(AppState::Briefing))
+        .with_child((
+            Text::new(locale.tr(definition.briefing)),
+            TextFont {
+                font: assets.font_fira.clone(),
+                font_size: 25.0,
+                ..default()
+            },
+            TextColor(Color::WHITE),
+            TextLayout::new_with_justify(Justify::Center),
+        ));
+}
+
+fn setup_mission(
+    mut commands: Commands,
+    mut freighter_spawn_events: MessageWriter<FreighterSpawnMessage>,
+    assets: Res<UiAssets>,
+    mission: Res<ActiveMission>,
+) {
+    commands.insert_resource(ObjectiveTracker::default());
+    if let Objective::Escort { from, to } = mission.definition().objective {
+        freighter_spawn_events.write(FreighterSpawnMessage {
+            from,
+            to,
+            speed: 30.0,
+        });
+    }
+    commands.spawn((
+        Node {
+            position_type: PositionType::Absolute,
+            top: Val::Px(10.0),
+            width: Val::Percent(100.0),
+            justify_content: JustifyContent::Center,
+            ..default()
+        },
+        DespawnOnExit(AppState::Game),
+        children![(
+            Text::new(""),
+            TextFont {
+                font: assets.font.clone(),
+                font_size: 25.0,
+                ..default()
+            },
+            TextColor(Color::srgb_u8(0xF8, 0xE4, 0x73)),
+            UiObjective,
+        )],
+    ));
+}
+
+// Any hit on the ship counts, even absorbed by its shield or extra lives.
+fn mission_on_damage(
+    damage: On<Damage>,
+    tracker: Option<ResMut<ObjectiveTracker>>,
+    ships: Query<(), With<Ship>>,
+) {
+    if let Some(mut tracker) = tracker
+        && ships.contains(damage.entity)
+    {
+        tracker.damaged = true;
+    }
+}
+
+// Asteroids destroyed by the player are counted, losing the freighter fails the mission.
+fn mission_on_destroyed(
+    destroyed: On<Destroyed>,
+    mut next_game_state: ResMut<NextState<GameState>>,
+    tracker: Option<ResMut<ObjectiveTracker>>,
+    asteroids: Query<&Asteroid>,
+    freighters: Query<(), With<Freighter>>,
+    ships: Query<(), With<Ship>>,
+) {
+    let Some(mut tracker) = tracker else {
+        return;
+    };
+    if let Ok(asteroid) = asteroids.get(destroyed.entity)
+        && destroyed
+            .source
+            .is_some_and(|source| ships.contains(source))
+    {
+        tracker.destroyed += 1;
+        if asteroid.material == AsteroidMaterial::Metal {
+            tracker.metal_destroyed += 1;
+        }
+    }
+    if freighters.contains(destroyed.entity) {
+        tracker.status = MissionStatus::Failed;
+        next_game_state.set(GameState::Over);
+    }
+}
+
+fn objective_tracker_system(
+    time: Res<Time>,
+    mut tracker: ResMut<ObjectiveTracker>,
+    mut next_game_state: ResMut<NextState<GameState>>,
+    mut crystal_events: MessageReader<CrystalCollectedMessage>,
+    mission: Res<ActiveMission>,
+    freighters: Query<(&Freighter, &Position)>,
+) {
+    let objective = &mission.definition().objective;
+    tracker.elapsed += time.delta_secs();
+    tracker.crystals_collected += crystal_events.read().count() as u32;
+    if let Objective::Escort { from, to } = objective
+        && let Ok((freighter, position)) = freighters.single()
+    {
+        tracker.escort_progress = if freighter.arrived {
+            1.0
+        } else {
+            (1.0 - position.0.distance(*to) / from.distance(*to)).clamp(0.0, 0.99)
+        };
+    }
+    if tracker.status == MissionStatus::InProgress && tracker.is_complete(objective) {
+        tracker.status = MissionStatus::Success;
+        next_game_state.set(GameState::Over);
+    }
+}
+
+// The kenvector font has no accented glyphs, the objective falls back to fira when needed.
+fn objective_hud_system(
+    tracker: Res<ObjectiveTracker>,
+    mission: Option<Res<ActiveMission>>,
+    locale: Res<Locale>,
+    assets: Res<UiAssets>,
+    mut query: Query<(&mut Text, &mut TextFont), With<UiObjective>>,
+) {
+    let Some(mission) = mission else {
+        return;
+    };
+    if tracker.is_changed() || locale.is_changed() {
+        for (mut text, mut font) in query.iter_mut() {
+            text.0 = tracker.description(&mission.definition().objective, &locale);
+            font.font = assets.font_for(&text.0);
+        }
+    }
+}
+
+// A completed mission earns one star, plus one for its `StarBonus`, plus one without being hit.
+// The ship being destroyed, or any other interruption, is a failure.
+fn spawn_mission_results(
+    mut commands: Commands,
+    mut tracker: ResMut<ObjectiveTracker>,
+    mut progress: ResMut<CampaignProgress>,
+    assets: Res<UiAssets>,
+    locale: Res<Locale>,
+    mission: Res<ActiveMission>,
+) {
+    if tracker.status == MissionStatus::InProgress {
+        tracker.status = MissionStatus::Failed;
+    }
+    let success = tracker.status == MissionStatus::Success;
+    tracker.stars = if success {
+        let bonus = tracker.has_bonus(&mission.definition().bonus);
+        1 + bonus as u32 + !tracker.damaged as u32
+    } else {
+        0
+    };
+    if progress.record(mission.index, tracker.stars) {
+        write_save(CAMPAIGN_SAVE_FILE, &*progress);
+    }
+    let has_next = mission.index + 1 < MISSIONS.len() && progress.is_unlocked(mission.index + 1);
+    // Retry and Next go through the mission briefing, which restarts the game.
+    let mut entries = vec![];
+    if has_next {
+        entries.push(MenuEntry::button(
+            "menu-next",
+            MenuCommand::run(|world| world.resource_mut::<ActiveMission>().index += 1)
+                .then(MenuCommand::Open(AppState::Briefing)),
+        ));
+    }
+    entries.push(MenuEntry::button(
+        "menu-retry",
+        MenuCommand::Open(AppState::Briefing),
+    ));
+    entries.push(MenuEntry::button(
+        "menu-campaign",
+        MenuCommand::Open(AppState::Campaign),
+    ));
+    let (main_text, main_text_color) = if success {
+        (
+            locale.format("mission-complete", &[("stars", &star_text(tracker.stars))]),
+            MenuTone::Title,
+        )
+    } else {
+        ("mission-failed".to_string(), MenuTone::Alert)
+    };
+    let entity = MenuHandler {
+        main_text,
+        main_text_color,
+        main_text_blink: false,
+        selected_id: 0,
+        entries,
+    }
+    .spawn(&mut commands, assets.font.clone());
+    commands
+        .entity(entity)
+        .insert(DespawnOnExit(GameState::Over));
+}
@@ -0,0 +1,110 @@
+use crate::prelude::*;
+
+const CRYSTAL_RADIUS: f32 = 8.0;
+// Seconds before an uncollected crystal vanishes
+const CRYSTAL_LIFETIME: f32 = 10.0;
+const CRYSTAL_COLOR: Color = Color::srgb(0.3, 1.0, 0.9);
+
+// A pickup dropped by asteroids, collected by flying over it.
+#[derive(Component)]
+pub struct Crystal {
+    pub lifetime: Timer,
+}
+
+#[derive(Message)]
+pub struct CrystalCollectedMessage;
+
+pub struct CrystalPlugin;
+
+impl Plugin for CrystalPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_message::<CrystalCollectedMessage>()
+            .add_observer(crystal_drop_on_asteroid_destroyed)
+            .add_systems(
+                FixedUpdate,
+                (crystal_collect_system, crystal_expiration_system)
+                    .run_if(in_state(GameState::Running)),
+            );
+    }
+}
+
+// Only asteroids destroyed by the player drop crystals, with the current wave chance.
+fn crystal_drop_on_asteroid_destroyed(
+    destroyed: On<Destroyed>,
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<ColorMaterial>>,
+    arena: Option<Res<Arena>>,
+    rng: Option<ResMut<GameRng>>,
+    settings: Res<Settings>,
+    asteroids: Query<(), With<Asteroid>>,
+    ships: Query<(), With<Ship>>,
+) {
+    let (Some(arena), Some(mut rng)) = (arena, rng) else {
+        return;
+    };
+    if !asteroids.contains(destroyed.entity)
+        || !destroyed
+            .source
+            .is_some_and(|source| ships.contains(source))
+    {
+        return;
+    }
+    let rng = &mut rng.drops;
+    if !rng.gen_bool(arena.crystal_chance.clamp(0.0, 1.0) as f64) {
+        return;
+    }
+    let mut crystal = commands.spawn((
+        Name::new("Crystal"),
+        Transform::from_translation(destroyed.position.extend(2.0)),
+        Crystal {
+            lifetime: Timer::from_seconds(CRYSTAL_LIFETIME, TimerMode::Once),
+        },
+        CollisionLayers::new(GameLayer::Pickup, [GameLayer::Player]),
+        RigidBody::Kinematic,
+        Collider::circle(CRYSTAL_RADIUS),
+        Sensor,
+        LinearVelocity(Vec2::from_angle(rng.gen_range(0.0..std::f32::consts::TAU)) * 20.0),
+        AngularVelocity(2.0),
+        DespawnOnExit(AppState::Game),
+    ));
+    match settings.render_style {
+        RenderStyle::Sprite => crystal.insert((
+            Mesh2d(meshes.add(RegularPolygon::new(CRYSTAL_RADIUS, 4))),
+            MeshMaterial2d(materials.add(CRYSTAL_COLOR)),
+        )),
+        RenderStyle::Vector => crystal.insert(VectorShape {
+            color: CRYSTAL_COLOR,
+            ..VectorShape::regular_polygon(CRYSTAL_RADIUS, 4)
+        }),
+    };
+}
+
+fn crystal_collect_system(
+    mut commands: Commands,
+    mut collected_events: MessageWriter<CrystalCollectedMessage>,
+    ships: Query<&CollidingEntities, With<Ship>>,
+    crystals: Query<(), With<Crystal>>,
+) {
+    for targets in ships.iter() {
+        for target in targets.iter() {
+            if crystals.contains(*target) {
+                collected_events.write(CrystalCollectedMessage);
+                commands.entity(*target).despawn();
+            }
+        }
+    }
+}
+
+fn crystal_expiration_system(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut crystals: Query<(Entity, &mut Crystal)>,
+) {
+    for (entity, mut crystal) in crystals.iter_mut() {
+        crystal.lifetime.tick(time.delta());
+        if crystal.lifetime.is_finished() {
+            commands.entity(entity).despawn();
+        }
+    }
+}
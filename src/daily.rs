@@ -0,0 +1,180 @@
+use std::collections::BTreeMap;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use rand::{SeedableRng, seq::SliceRandom};
+use rand_chacha::ChaCha8Rng;
+use serde::{Deserialize, Serialize};
+
+use crate::prelude::*;
+
+pub const DAILY_SAVE_FILE: &str = "daily.ron";
+const DAILY_MUTATOR_COUNT: usize = 2;
+
+// Challenge of the day: every player gets the same seed, thus the same asteroids, and mutators.
+#[derive(Debug, Clone, Resource)]
+pub struct DailyChallenge {
+    // Days since the Unix epoch, in UTC
+    pub day: u64,
+    pub seed: u64,
+    pub mutators: Vec<Mutator>,
+}
+impl DailyChallenge {
+    pub fn today() -> Self {
+        let seconds = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |duration| duration.as_secs());
+        DailyChallenge::for_day(seconds / 86400)
+    }
+    pub fn for_day(day: u64) -> Self {
+        // Spread consecutive days over the seed space
+        let seed = day.wrapping_mul(0x9E37_79B9_7F4A_7C15) ^ 0x4B41_5441_5354_4552;
+        let mut rng = ChaCha8Rng::seed_from_u64(seed);
+        let mutators = Mutator::ALL
+            .choose_multiple(&mut rng, DAILY_MUTATOR_COUNT)
+            .copied()
+            .collect();
+        DailyChallenge {
+            day,
+            seed,
+            mutators,
+        }
+    }
+    // The challenge date, formatted as YYYY-MM-DD
+    pub fn date(&self) -> String {
+        // Civil from days algorithm, see http://howardhinnant.github.io/date_algorithms.html
+        let z = self.day as i64 + 719468;
+        let era = z.div_euclid(146097);
+        let doe = z.rem_euclid(146097);
+        let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146096) / 365;
+        let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+        let mp = (5 * doy + 2) / 153;
+        let day = doy - (153 * mp + 2) / 5 + 1;
+        let month = if mp < 10 { mp + 3 } else { mp - 9 };
+        let year = yoe + era * 400 + (month <= 2) as i64;
+        format!("{:04}-{:02}-{:02}", year, month, day)
+    }
+}
+
+// Local best score of each daily challenge, by seed.
+#[derive(Debug, Default, Resource, Serialize, Deserialize)]
+pub struct DailyScores {
+    pub best: BTreeMap<u64, u32>,
+}
+
+pub struct DailyPlugin;
+
+impl Plugin for DailyPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(load_save::<DailyScores>(DAILY_SAVE_FILE))
+            .add_systems(OnEnter(AppState::Menu), |mut commands: Commands| {
+                commands.remove_resource::<DailyChallenge>();
+            })
+            .add_systems(OnEnter(AppState::Daily), spawn_daily_menu)
+            .add_systems(
+                OnEnter(GameState::Over),
+                record_daily_score.run_if(resource_exists::<DailyChallenge>),
+            );
+    }
+}
+
+// The challenge is presented with its mutators before being started.
+fn spawn_daily_menu(
+    mut commands: Commands,
+    assets: Res<UiAssets>,
+    locale: Res<Locale>,
+    scores: Res<DailyScores>,
+) {
+    let challenge = DailyChallenge::today();
+    let mutators = challenge
+        .mutators
+        .iter()
+        .map(|mutator| locale.tr(mutator.key()))
+        .collect::<Vec<_>>()
+        .join(", ");
+    let best = scores
+        .best
+        .get(&challenge.seed)
+        .map_or("-".to_string(), |score| score.to_string());
+    let description = locale.format(
+        "daily-description",
+        &[
+            ("date", &challenge.date()),
+            ("mutators", &mutators),
+            ("best", &best),
+        ],
+    );
+    let start = MenuCommand::run(move |world| {
+        world.resource_mut::<SelectedLevel>().0 = None;
+        world.resource_mut::<Mutators>().0 = challenge.mutators.clone();
+        world.insert_resource(challenge.clone());
+    })
+    .then(MenuCommand::Open(AppState::Game));
+    let entity = MenuHandler {
+        main_text: "menu-daily".into(),
+        main_text_color: MenuTone::Title,
+        main_text_blink: false,
+        selected_id: 0,
+        entries: vec![
+            MenuEntry::button("menu-start", start),
+            MenuEntry::button("menu-back", MenuCommand::Back),
+        ],
+    }
+    .spawn(&mut commands, assets.font.clone());
+    commands
+        .entity(entity)
+        .insert(DespawnOnExit(AppState::Daily))
+        .with_child((
+            Text::new(description),
+            TextFont {
+                font: assets.font_fira.clone(),
+                font_size: 25.0,
+                ..default()
+            },
+            TextColor(Color::WHITE),
+            TextLayout::new_with_justify(Justify::Center),
+        ));
+}
+
+fn record_daily_score(
+    arena: Res<Arena>,
+    challenge: Res<DailyChallenge>,
+    mut scores: ResMut<DailyScores>,
+) {
+    let best = scores.best.entry(challenge.seed).or_insert(0);
+    if arena.score > *best {
+        *best = arena.score;
+        write_save(DAILY_SAVE_FILE, &*scores);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn date_from_day() {
+        assert_eq!(DailyChallenge::for_day(0).date(), "1970-01-01");
+        assert_eq!(DailyChallenge::for_day(11016).date(), "2000-02-29");
+        assert_eq!(DailyChallenge::for_day(19782).date(), "2024-02-29");
+        assert_eq!(DailyChallenge::for_day(20088).date(), "2024-12-31");
+        assert_eq!(DailyChallenge::for_day(20745).date(), "2026-10-19");
+    }
+
+    #[test]
+    fn seed_is_stable_and_differs_between_days() {
+        let today = DailyChallenge::for_day(20745);
+        assert_eq!(today.seed, DailyChallenge::for_day(20745).seed);
+        assert_ne!(today.seed, DailyChallenge::for_day(20744).seed);
+        assert_ne!(today.seed, DailyChallenge::for_day(20746).seed);
+    }
+
+    #[test]
+    fn mutators_are_distinct_and_stable() {
+        for day in 20000..20100 {
+            let challenge = DailyChallenge::for_day(day);
+            assert_eq!(challenge.mutators.len(), DAILY_MUTATOR_COUNT);
+            assert_ne!(challenge.mutators[0], challenge.mutators[1]);
+            assert_eq!(challenge.mutators, DailyChallenge::for_day(day).mutators);
+        }
+    }
+}
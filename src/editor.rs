@@ -0,0 +1,751 @@
+use bevy::window::PrimaryWindow;
+
+use crate::prelude::*;
+
+// Width of the tool and inspector panel, on the right of the window
+const PANEL_WIDTH: f32 = 260.0;
+// Distance under which a click picks a point-like item, like spawners or mines
+const PICK_RADIUS: f32 = 20.0;
+const SELECTED_COLOR: Color = Color::srgb(1.0, 0.9, 0.2);
+const BUTTON_BG: Color = Color::srgb(0.1, 0.1, 0.1);
+const TOOL_SELECTED_BG: Color = Color::srgb(0.0, 0.35, 0.35);
+
+// What a left click on an empty spot of the arena places.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum EditorTool {
+    Wall,
+    Station,
+    Mine,
+    AsteroidSpawn,
+    GravityWell,
+    HazardZone,
+    PlayerStart,
+}
+impl EditorTool {
+    pub const ALL: [EditorTool; 7] = [
+        EditorTool::Wall,
+        EditorTool::Station,
+        EditorTool::Mine,
+        EditorTool::AsteroidSpawn,
+        EditorTool::GravityWell,
+        EditorTool::HazardZone,
+        EditorTool::PlayerStart,
+    ];
+    // `Locale` key of the tool name
+    pub fn key(&self) -> &'static str {
+        match self {
+            EditorTool::Wall => "editor-wall",
+            EditorTool::Station => "editor-station",
+            EditorTool::Mine => "editor-mine",
+            EditorTool::AsteroidSpawn => "editor-asteroid-spawn",
+            EditorTool::GravityWell => "editor-gravity-well",
+            EditorTool::HazardZone => "editor-hazard-zone",
+            EditorTool::PlayerStart => "editor-player-start",
+        }
+    }
+}
+
+// Reference to one element of the edited level, by its index in the level lists.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum EditorItem {
+    PlayerStart,
+    AsteroidSpawn(usize),
+    Obstacle(usize),
+    GravityWell(usize),
+    HazardZone(usize),
+}
+impl EditorItem {
+    // Localized name, numbered for items of the level lists
+    pub fn label(&self, locale: &Locale) -> String {
+        let (key, index) = match *self {
+            EditorItem::PlayerStart => return locale.tr("editor-player-start"),
+            EditorItem::AsteroidSpawn(i) => ("editor-asteroid-spawn", i),
+            EditorItem::Obstacle(i) => ("editor-obstacle", i),
+            EditorItem::GravityWell(i) => ("editor-gravity-well", i),
+            EditorItem::HazardZone(i) => ("editor-hazard-zone", i),
+        };
+        format!("{} {}", locale.tr(key), index + 1)
+    }
+}
+
+// Item properties that can be tweaked from the inspector.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum EditorProperty {
+    X,
+    Y,
+    Rotation,
+    Width,
+    Height,
+    Radius,
+    Strength,
+    CoreRadius,
+}
+impl EditorProperty {
+    // `Locale` key of the property name
+    pub fn key(&self) -> &'static str {
+        match self {
+            EditorProperty::X => "editor-x",
+            EditorProperty::Y => "editor-y",
+            EditorProperty::Rotation => "editor-rotation",
+            EditorProperty::Width => "editor-width",
+            EditorProperty::Height => "editor-height",
+            EditorProperty::Radius => "editor-radius",
+            EditorProperty::Strength => "editor-strength",
+            EditorProperty::CoreRadius => "editor-core-radius",
+        }
+    }
+    // Increment applied by the inspector `-` and `+` buttons
+    pub fn step(&self) -> f32 {
+        match self {
+            EditorProperty::X | EditorProperty::Y => 10.0,
+            EditorProperty::Rotation => 0.1,
+            EditorProperty::Width | EditorProperty::Height => 10.0,
+            EditorProperty::Radius | EditorProperty::CoreRadius => 5.0,
+            EditorProperty::Strength => 250_000.0,
+        }
+    }
+}
+
+// State of the level editor, kept when test playing the level.
+#[derive(Debug, Resource)]
+pub struct Editor {
+    pub level: Level,
+    pub tool: EditorTool,
+    pub selected: Option<EditorItem>,
+    // The selected item follows the mouse while the left button is held
+    pub dragging: bool,
+}
+impl Editor {
+    pub fn position(&self, item: EditorItem) -> Vec2 {
+        match item {
+            EditorItem::PlayerStart => self.level.player_start,
+            EditorItem::AsteroidSpawn(i) => self.level.asteroid_spawns[i],
+            EditorItem::Obstacle(i) => self.level.obstacles[i].position,
+            EditorItem::GravityWell(i) => self.level.gravity_wells[i].position,
+            EditorItem::HazardZone(i) => self.level.hazard_zones[i].position,
+        }
+    }
+    pub fn set_position(&mut self, item: EditorItem, position: Vec2) {
+        match item {
+            EditorItem::PlayerStart => self.level.player_start = position,
+            EditorItem::AsteroidSpawn(i) => self.level.asteroid_spawns[i] = position,
+            EditorItem::Obstacle(i) => self.level.obstacles[i].position = position,
+            EditorItem::GravityWell(i) => self.level.gravity_wells[i].position = position,
+            EditorItem::HazardZone(i) => self.level.hazard_zones[i].position = position,
+        }
+    }
+    // Radius under which a click selects the item
+    fn pick_radius(&self, item: EditorItem) -> f32 {
+        match item {
+            EditorItem::Obstacle(i) => match self.level.obstacles[i].kind {
+                ObstacleKind::Wall { size } => size.max_element() / 2.0,
+                ObstacleKind::Station { radius } => radius,
+                ObstacleKind::Mine => PICK_RADIUS,
+            },
+            EditorItem::GravityWell(i) => self.level.gravity_wells[i].core_radius.max(PICK_RADIUS),
+            EditorItem::HazardZone(i) => self.level.hazard_zones[i].radius,
+            _ => PICK_RADIUS,
+        }
+    }
+    fn items(&self) -> Vec<EditorItem> {
+        let level = &self.level;
+        std::iter::once(EditorItem::PlayerStart)
+            .chain((0..level.asteroid_spawns.len()).map(EditorItem::AsteroidSpawn))
+            .chain((0..level.obstacles.len()).map(EditorItem::Obstacle))
+            .chain((0..level.gravity_wells.len()).map(EditorItem::GravityWell))
+            .chain((0..level.hazard_zones.len()).map(EditorItem::HazardZone))
+            .collect()
+    }
+    // The closest item whose pick area contains `position`.
+    pub fn item_at(&self, position: Vec2) -> Option<EditorItem> {
+        self.items()
+            .into_iter()
+            .map(|item| (item, self.position(item).distance(position)))
+            .filter(|(item, distance)| *distance <= self.pick_radius(*item))
+            .min_by(|a, b| a.1.total_cmp(&b.1))
+            .map(|(item, _)| item)
+    }
+    // Add a new item of the current tool kind, with default properties.
+    pub fn place(&mut self, position: Vec2) -> EditorItem {
+        let level = &mut self.level;
+        let obstacle = |kind| ObstacleDefinition {
+            kind,
+            position,
+            rotation: 0.0,
+        };
+        match self.tool {
+            EditorTool::Wall => level.obstacles.push(obstacle(ObstacleKind::Wall {
+                size: Vec2::new(200.0, 16.0),
+            })),
+            EditorTool::Station => level
+                .obstacles
+                .push(obstacle(ObstacleKind::Station { radius: 50.0 })),
+            EditorTool::Mine => level.obstacles.push(obstacle(ObstacleKind::Mine)),
+            EditorTool::AsteroidSpawn => level.asteroid_spawns.push(position),
+            EditorTool::GravityWell => level.gravity_wells.push(GravityWellDefinition {
+                position,
+                strength: 2_000_000.0,
+                core_radius: 20.0,
+            }),
+            EditorTool::HazardZone => level.hazard_zones.push(HazardZoneDefinition {
+                position,
+                radius: 100.0,
+            }),
+            EditorTool::PlayerStart => level.player_start = position,
+        }
+        match self.tool {
+            EditorTool::Wall | EditorTool::Station | EditorTool::Mine => {
+                EditorItem::Obstacle(level.obstacles.len() - 1)
+            }
+            EditorTool::AsteroidSpawn => EditorItem::AsteroidSpawn(level.asteroid_spawns.len() - 1),
+            EditorTool::GravityWell => EditorItem::GravityWell(level.gravity_wells.len() - 1),
+            EditorTool::HazardZone => EditorItem::HazardZone(level.hazard_zones.len() - 1),
+            EditorTool::PlayerStart => EditorItem::PlayerStart,
+        }
+    }
+    // The player start can only be moved, not removed.
+    // Removing shifts the indices of the following items, so the selection is cleared.
+    pub fn remove(&mut self, item: EditorItem) {
+        match item {
+            EditorItem::PlayerStart => {}
+            EditorItem::AsteroidSpawn(i) => {
+                self.level.asteroid_spawns.remove(i);
+            }
+            EditorItem::Obstacle(i) => {
+                self.level.obstacles.remove(i);
+            }
+            EditorItem::GravityWell(i) => {
+                self.level.gravity_wells.remove(i);
+            }
+            EditorItem::HazardZone(i) => {
+                self.level.hazard_zones.remove(i);
+            }
+        }
+        self.selected = None;
+    }
+    pub fn properties(&self, item: EditorItem) -> Vec<(EditorProperty, f32)> {
+        let position = self.position(item);
+        let mut properties = vec![
+            (EditorProperty::X, position.x),
+            (EditorProperty::Y, position.y),
+        ];
+        match item {
+            EditorItem::Obstacle(i) => {
+                let obstacle = &self.level.obstacles[i];
+                match obstacle.kind {
+                    ObstacleKind::Wall { size } => {
+                        properties.push((EditorProperty::Width, size.x));
+                        properties.push((EditorProperty::Height, size.y));
+                        properties.push((EditorProperty::Rotation, obstacle.rotation));
+                    }
+                    ObstacleKind::Station { radius } => {
+                        properties.push((EditorProperty::Radius, radius))
+                    }
+                    ObstacleKind::Mine => {}
+                }
+            }
+            EditorItem::GravityWell(i) => {
+                let well = &self.level.gravity_wells[i];
+                properties.push((EditorProperty::Strength, well.strength));
+                properties.push((EditorProperty::CoreRadius, well.core_radius));
+            }
+            EditorItem::HazardZone(i) => {
+                properties.push((EditorProperty::Radius, self.level.hazard_zones[i].radius))
+            }
+            _ => {}
+        }
+        properties
+    }
+    // Sizes are kept strictly positive, whatever the number of decrements.
+    pub fn adjust(&mut self, item: EditorItem, property: EditorProperty, delta: f32) {
+        let shrink = |value: f32| (value + delta).max(property.step());
+        match property {
+            EditorProperty::X => {
+                let position = self.position(item) + Vec2::X * delta;
+                self.set_position(item, position);
+            }
+            EditorProperty::Y => {
+                let position = self.position(item) + Vec2::Y * delta;
+                self.set_position(item, position);
+            }
+            _ => match item {
+                EditorItem::Obstacle(i) => {
+                    let obstacle = &mut self.level.obstacles[i];
+                    match (&mut obstacle.kind, property) {
+                        (_, EditorProperty::Rotation) => obstacle.rotation += delta,
+                        (ObstacleKind::Wall { size }, EditorProperty::Width) => {
+                            size.x = shrink(size.x)
+                        }
+                        (ObstacleKind::Wall { size }, EditorProperty::Height) => {
+                            size.y = shrink(size.y)
+                        }
+                        (ObstacleKind::Station { radius }, EditorProperty::Radius) => {
+                            *radius = shrink(*radius)
+                        }
+                        _ => {}
+                    }
+                }
+                EditorItem::GravityWell(i) => {
+                    let well = &mut self.level.gravity_wells[i];
+                    match property {
+                        EditorProperty::Strength => well.strength = shrink(well.strength),
+                        EditorProperty::CoreRadius => well.core_radius = shrink(well.core_radius),
+                        _ => {}
+                    }
+                }
+                EditorItem::HazardZone(i) => {
+                    let zone = &mut self.level.hazard_zones[i];
+                    zone.radius = shrink(zone.radius);
+                }
+                _ => {}
+            },
+        }
+    }
+}
+
+// Inserted when the game has been started from the editor "Test play" button.
+#[derive(Resource)]
+pub struct EditorPlaytest;
+
+// Actions of the editor panel buttons
+#[derive(Component, Debug, Copy, Clone)]
+pub enum EditorButton {
+    Tool(EditorTool),
+    Adjust(EditorProperty, f32),
+    Delete,
+    Save,
+    TestPlay,
+    Menu,
+}
+
+// Root of the editor panel, rebuilt when the language changes
+#[derive(Component)]
+pub struct EditorPanel;
+
+// Panel node whose content is rebuilt when the selection or its properties change
+#[derive(Component)]
+pub struct EditorInspector;
+
+pub struct EditorPlugin;
+
+impl Plugin for EditorPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            OnEnter(AppState::Editor),
+            (setup_editor, spawn_editor_panel).chain(),
+        )
+        .add_systems(
+            Update,
+            (
+                editor_button_system,
+                editor_mouse_system,
+                editor_keyboard_system,
+                spawn_editor_panel.run_if(resource_changed::<Locale>),
+                editor_tool_color_system,
+                editor_inspector_system.run_if(
+                    resource_changed::<Editor>.or(any_match_filter::<Added<EditorInspector>>),
+                ),
+                draw_editor_level,
+            )
+                .chain()
+                .run_if(in_state(AppState::Editor)),
+        );
+    }
+}
+
+// The editor resumes the level being edited, or starts from the previously saved custom level.
+fn setup_editor(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    levels: Res<Assets<Level>>,
+    editor: Option<Res<Editor>>,
+) {
+    commands.remove_resource::<EditorPlaytest>();
+    if editor.is_none() {
+        let level = asset_server
+            .get_handle::<Level>(CUSTOM_LEVEL_ASSET)
+            .and_then(|handle| levels.get(&handle).cloned())
+            .unwrap_or_else(|| Level {
+                name: "Custom".into(),
+                ..default()
+            });
+        commands.insert_resource(Editor {
+            level,
+            tool: EditorTool::Wall,
+            selected: None,
+            dragging: false,
+        });
+    }
+}
+
+fn editor_button(
+    assets: &UiAssets,
+    label: String,
+    button: EditorButton,
+    width: f32,
+) -> impl Bundle {
+    let font = assets.font_for(&label);
+    (
+        Button,
+        button,
+        Node {
+            width: Val::Px(width),
+            height: Val::Px(26.0),
+            margin: UiRect::all(Val::Px(2.0)),
+            justify_content: JustifyContent::Center,
+            align_items: AlignItems::Center,
+            ..default()
+        },
+        BorderRadius::all(Val::Px(4.0)),
+        BackgroundColor(BUTTON_BG),
+        children![(
+            Text::new(label),
+            TextFont {
+                font,
+                font_size: 16.0,
+                ..default()
+            },
+            TextColor(Color::srgb(0.0, 0.7, 0.7)),
+        )],
+    )
+}
+
+// Replaces the previous panel, if any, so that it follows the language.
+fn spawn_editor_panel(
+    mut commands: Commands,
+    assets: Res<UiAssets>,
+    locale: Res<Locale>,
+    panels: Query<Entity, With<EditorPanel>>,
+) {
+    for panel in panels.iter() {
+        commands.entity(panel).despawn();
+    }
+    let assets = &*assets;
+    commands
+        .spawn((
+            Name::new("EditorPanel"),
+            EditorPanel,
+            Node {
+                position_type: PositionType::Absolute,
+                right: Val::Px(0.0),
+                width: Val::Px(PANEL_WIDTH),
+                height: Val::Percent(100.0),
+                flex_direction: FlexDirection::Column,
+                padding: UiRect::all(Val::Px(8.0)),
+                ..default()
+            },
+            BackgroundColor(Color::srgba(0.0, 0.0, 0.0, 0.8)),
+            DespawnOnExit(AppState::Editor),
+        ))
+        .with_children(|panel| {
+            panel
+                .spawn(Node {
+                    flex_wrap: FlexWrap::Wrap,
+                    ..default()
+                })
+                .with_children(|tools| {
+                    for tool in EditorTool::ALL {
+                        tools.spawn(editor_button(
+                            assets,
+                            locale.tr(tool.key()),
+                            EditorButton::Tool(tool),
+                            118.0,
+                        ));
+                    }
+                });
+            panel.spawn((
+                EditorInspector,
+                Node {
+                    flex_direction: FlexDirection::Column,
+                    flex_grow: 1.0,
+                    margin: UiRect::vertical(Val::Px(12.0)),
+                    ..default()
+                },
+            ));
+            panel.spawn((
+                Text::new(locale.tr("editor-help")),
+                TextFont {
+                    font: assets.font_fira.clone(),
+                    font_size: 14.0,
+                    ..default()
+                },
+                TextColor(Color::srgb(0.5, 0.5, 0.5)),
+            ));
+            panel.spawn(editor_button(
+                assets,
+                locale.tr("editor-save"),
+                EditorButton::Save,
+                240.0,
+            ));
+            panel.spawn(editor_button(
+                assets,
+                locale.tr("editor-test-play"),
+                EditorButton::TestPlay,
+                240.0,
+            ));
+            panel.spawn(editor_button(
+                assets,
+                locale.tr("editor-menu"),
+                EditorButton::Menu,
+                240.0,
+            ));
+        });
+}
+
+fn editor_button_system(
+    mut commands: Commands,
+    mut editor: ResMut<Editor>,
+    mut selected_level: ResMut<SelectedLevel>,
+    mut level_assets: ResMut<LevelAssets>,
+    mut next_app_state: ResMut<NextState<AppState>>,
+    asset_server: Res<AssetServer>,
+    buttons: Query<(&Interaction, &EditorButton), Changed<Interaction>>,
+) {
+    for (interaction, button) in buttons.iter() {
+        if *interaction != Interaction::Pressed {
+            continue;
+        }
+        match *button {
+            EditorButton::Tool(tool) => editor.tool = tool,
+            EditorButton::Adjust(property, delta) => {
+                if let Some(item) = editor.selected {
+                    editor.adjust(item, property, delta);
+                }
+            }
+            EditorButton::Delete => {
+                if let Some(item) = editor.selected {
+                    editor.remove(item);
+                }
+            }
+            EditorButton::Save => save_level(&editor.level, &mut level_assets, &asset_server),
+            EditorButton::TestPlay => {
+                selected_level.0 = Some(editor.level.clone());
+                commands.insert_resource(EditorPlaytest);
+                next_app_state.set(AppState::Game);
+            }
+            EditorButton::Menu => next_app_state.set(AppState::Menu),
+        }
+    }
+}
+
+// Write the level in the save directory, where it is picked up by the level selection menu.
+fn save_level(level: &Level, level_assets: &mut LevelAssets, asset_server: &AssetServer) {
+    let path = save_path(CUSTOM_LEVEL_FILE);
+    match write_file(&path, level) {
+        Ok(()) => {
+            info!("Level saved to {}", path.display());
+            match asset_server.get_handle::<Level>(CUSTOM_LEVEL_ASSET) {
+                Some(handle) if level_assets.levels.contains(&handle) => {
+                    asset_server.reload(CUSTOM_LEVEL_ASSET)
+                }
+                _ => level_assets
+                    .levels
+                    .push(asset_server.load(CUSTOM_LEVEL_ASSET)),
+            }
+        }
+        Err(error) => error!("Failed to save level to {}: {}", path.display(), error),
+    }
+}
+
+// Left click selects the item under the cursor, or places a new one, then drags it.
+// Right click removes the item under the cursor.
+fn editor_mouse_system(
+    mut editor: ResMut<Editor>,
+    mouse: Res<ButtonInput<MouseButton>>,
+    windows: Query<&Window, With<PrimaryWindow>>,
+    cameras: Query<(&Camera, &GlobalTransform)>,
+) {
+    let Ok(window) = windows.single() else {
+        return;
+    };
+    let Some(cursor) = window.cursor_position() else {
+        return;
+    };
+    if !mouse.pressed(MouseButton::Left) && editor.dragging {
+        editor.dragging = false;
+    }
+    // Clicks on the panel are handled by the buttons
+    if cursor.x > window.width() - PANEL_WIDTH && !editor.dragging {
+        return;
+    }
+    let Some(position) = cameras
+        .iter()
+        .find_map(|(camera, transform)| camera.viewport_to_world_2d(transform, cursor).ok())
+    else {
+        return;
+    };
+    if mouse.just_pressed(MouseButton::Left) {
+        let item = editor
+            .item_at(position)
+            .unwrap_or_else(|| editor.place(position));
+        editor.selected = Some(item);
+        editor.dragging = true;
+    } else if editor.dragging
+        && let Some(item) = editor.selected
+        && editor.position(item) != position
+    {
+        editor.set_position(item, position);
+    }
+    if mouse.just_pressed(MouseButton::Right)
+        && let Some(item) = editor.item_at(position)
+    {
+        editor.remove(item);
+    }
+}
+
+fn editor_keyboard_system(mut editor: ResMut<Editor>, keyboard: Res<ButtonInput<KeyCode>>) {
+    if keyboard.any_just_pressed([KeyCode::Delete, KeyCode::Backspace])
+        && let Some(item) = editor.selected
+    {
+        editor.remove(item);
+    }
+}
+
+fn editor_tool_color_system(
+    editor: Res<Editor>,
+    mut buttons: Query<(&EditorButton, &mut BackgroundColor)>,
+) {
+    for (button, mut bg_color) in buttons.iter_mut() {
+        if let EditorButton::Tool(tool) = button {
+            bg_color.0 = if *tool == editor.tool {
+                TOOL_SELECTED_BG
+            } else {
+                BUTTON_BG
+            };
+        }
+    }
+}
+
+// One row per property of the selected item, with buttons to decrease and increase it.
+fn editor_inspector_system(
+    mut commands: Commands,
+    editor: Res<Editor>,
+    assets: Res<UiAssets>,
+    locale: Res<Locale>,
+    inspector: Query<Entity, With<EditorInspector>>,
+) {
+    let Ok(inspector) = inspector.single() else {
+        return;
+    };
+    let assets = &*assets;
+    let text_font = TextFont {
+        font: assets.font_fira.clone(),
+        font_size: 16.0,
+        ..default()
+    };
+    commands
+        .entity(inspector)
+        .despawn_related::<Children>()
+        .with_children(|parent| {
+            let Some(item) = editor.selected else {
+                parent.spawn((
+                    Text::new(locale.format("editor-level", &[("name", &editor.level.name)])),
+                    text_font.clone(),
+                ));
+                return;
+            };
+            parent.spawn((Text::new(item.label(&locale)), text_font.clone()));
+            for (property, value) in editor.properties(item) {
+                parent
+                    .spawn(Node {
+                        align_items: AlignItems::Center,
+                        ..default()
+                    })
+                    .with_children(|row| {
+                        row.spawn((
+                            Text::new(format!("{}: {:.1}", locale.tr(property.key()), value)),
+                            text_font.clone(),
+                            Node {
+                                width: Val::Px(170.0),
+                                ..default()
+                            },
+                        ));
+                        row.spawn(editor_button(
+                            assets,
+                            "-".into(),
+                            EditorButton::Adjust(property, -property.step()),
+                            30.0,
+                        ));
+                        row.spawn(editor_button(
+                            assets,
+                            "+".into(),
+                            EditorButton::Adjust(property, property.step()),
+                            30.0,
+                        ));
+                    });
+            }
+            if item != EditorItem::PlayerStart {
+                parent.spawn(editor_button(
+                    assets,
+                    locale.tr("editor-delete"),
+                    EditorButton::Delete,
+                    240.0,
+                ));
+            }
+        });
+}
+
+// The edited level is drawn with gizmos, no gameplay entity is spawned in the editor.
+fn draw_editor_level(editor: Res<Editor>, mut gizmos: Gizmos) {
+    let color = |item| {
+        if editor.selected == Some(item) {
+            SELECTED_COLOR
+        } else {
+            Color::WHITE
+        }
+    };
+    gizmos.rect_2d(
+        Vec2::ZERO,
+        Vec2::new(ARENA_WIDTH, ARENA_HEIGHT),
+        Color::srgb(0.2, 0.2, 0.2),
+    );
+    let level = &editor.level;
+    let ship = VectorShape::ship();
+    gizmos.linestrip_2d(
+        ship.points
+            .iter()
+            .chain(ship.points.first())
+            .map(|point| level.player_start + *point),
+        color(EditorItem::PlayerStart),
+    );
+    for (i, spawn) in level.asteroid_spawns.iter().enumerate() {
+        let color = color(EditorItem::AsteroidSpawn(i));
+        gizmos.line_2d(
+            *spawn - Vec2::splat(10.0),
+            *spawn + Vec2::splat(10.0),
+            color,
+        );
+        gizmos.line_2d(
+            *spawn + Vec2::new(-10.0, 10.0),
+            *spawn + Vec2::new(10.0, -10.0),
+            color,
+        );
+        gizmos.circle_2d(*spawn, 14.0, color);
+    }
+    for (i, obstacle) in level.obstacles.iter().enumerate() {
+        let color = color(EditorItem::Obstacle(i));
+        let isometry = Isometry2d::new(obstacle.position, Rot2::radians(obstacle.rotation));
+        match obstacle.kind {
+            ObstacleKind::Wall { size } => {
+                gizmos.rect_2d(isometry, size, color);
+            }
+            ObstacleKind::Station { radius } => {
+                gizmos.circle_2d(isometry, radius, color).resolution(24);
+            }
+            ObstacleKind::Mine => {
+                gizmos.circle_2d(isometry, MINE_RADIUS, color).resolution(8);
+            }
+        }
+    }
+    for (i, well) in level.gravity_wells.iter().enumerate() {
+        let color = color(EditorItem::GravityWell(i));
+        gizmos.circle_2d(well.position, well.core_radius, color);
+        gizmos.circle_2d(well.position, well.core_radius * 3.0, color.with_alpha(0.4));
+    }
+    for (i, zone) in level.hazard_zones.iter().enumerate() {
+        let color = color(EditorItem::HazardZone(i));
+        gizmos
+            .circle_2d(zone.position, zone.radius, color)
+            .resolution(64);
+    }
+}
@@ -4,6 +4,8 @@ pub enum ExplosionKind {
     ShipDead,
     ShipContact,
     LaserOnAsteroid,
+    LaserOnObstacle,
+    MineDetonation,
 }
 #[derive(Message)]
 pub struct SpawnExplosionMessage {
@@ -38,6 +40,7 @@ fn catch_explosion_event(
     mut event_reader: MessageReader<SpawnExplosionMessage>,
     handles: Res<SpriteAssets>,
     audios: Res<AudioAssets>,
+    mut camera_effect_events: MessageWriter<CameraEffectMessage>,
 ) {
     for event in event_reader.read() {
         let (texture, sound, start_size, end_scale, duration) = match event.kind {
@@ -62,7 +65,33 @@ fn catch_explosion_event(
                 1.5,
                 1.,
             ),
+            ExplosionKind::LaserOnObstacle => (
+                handles.ship_contact.clone(),
+                audios.ship_contact.clone(),
+                Vec2::new(21., 19.),
+                1.2,
+                0.5,
+            ),
+            ExplosionKind::MineDetonation => (
+                handles.ship_explosion.clone(),
+                audios.asteroid_explosion.clone(),
+                Vec2::new(42., 39.),
+                3.,
+                1.5,
+            ),
         };
+        // Bigger explosions shake the camera more, the biggest ones also punch the zoom
+        let (trauma, zoom) = match event.kind {
+            ExplosionKind::ShipDead => (0.8, 0.15),
+            ExplosionKind::ShipContact => (0.4, 0.0),
+            ExplosionKind::LaserOnAsteroid => (0.1, 0.0),
+            ExplosionKind::LaserOnObstacle => (0.05, 0.0),
+            ExplosionKind::MineDetonation => (0.6, 0.1),
+        };
+        camera_effect_events.write(CameraEffectMessage::Shake(trauma));
+        if zoom > 0.0 {
+            camera_effect_events.write(CameraEffectMessage::ZoomPunch(zoom));
+        }
         commands.spawn((
             Sprite {
                 image: texture,
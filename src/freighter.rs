@@ -0,0 +1,159 @@
+use crate::prelude::*;
+
+const FREIGHTER_SIZE: Vec2 = Vec2::new(60.0, 24.0);
+const FREIGHTER_LIFE: u32 = 5;
+// Seconds during which the freighter cannot be damaged again after a hit
+const FREIGHTER_HIT_COOLDOWN: f32 = 1.0;
+
+// A slow transport crossing the arena toward `target`, to be protected from asteroids.
+#[derive(Component)]
+pub struct Freighter {
+    pub target: Vec2,
+    pub speed: f32,
+    pub arrived: bool,
+    pub hit_cooldown: Timer,
+}
+
+#[derive(Message)]
+pub struct FreighterSpawnMessage {
+    pub from: Vec2,
+    pub to: Vec2,
+    pub speed: f32,
+}
+
+pub struct FreighterPlugin;
+
+impl Plugin for FreighterPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_message::<FreighterSpawnMessage>().add_systems(
+            FixedUpdate,
+            (
+                spawn_freighter,
+                freighter_movement_system,
+                freighter_asteroid_collision,
+            )
+                .chain()
+                .run_if(in_state(GameState::Running)),
+        );
+    }
+}
+
+fn spawn_freighter(
+    mut commands: Commands,
+    mut spawn_events: MessageReader<FreighterSpawnMessage>,
+    settings: Res<Settings>,
+) {
+    for event in spawn_events.read() {
+        let mut hit_cooldown = Timer::from_seconds(FREIGHTER_HIT_COOLDOWN, TimerMode::Once);
+        hit_cooldown.tick(hit_cooldown.duration());
+        let direction = (event.to - event.from).normalize_or_zero();
+        let mut freighter = commands.spawn((
+            Name::new("Freighter"),
+            Transform::from_translation(event.from.extend(1.0))
+                .with_rotation(Quat::from_rotation_z(direction.to_angle())),
+            Freighter {
+                target: event.to,
+                speed: event.speed,
+                arrived: false,
+                hit_cooldown,
+            },
+            Health::new(FREIGHTER_LIFE),
+            CollisionLayers::new(GameLayer::Player, [GameLayer::Asteroid]),
+            CollidingEntities::default(),
+            RigidBody::Kinematic,
+            Collider::rectangle(FREIGHTER_SIZE.x, FREIGHTER_SIZE.y),
+            LinearVelocity(direction * event.speed),
+            DespawnOnExit(AppState::Game),
+        ));
+        match settings.render_style {
+            RenderStyle::Sprite => freighter.insert(Sprite::from_color(
+                Color::srgb(0.7, 0.6, 0.3),
+                FREIGHTER_SIZE,
+            )),
+            RenderStyle::Vector => freighter.insert(VectorShape::rectangle(FREIGHTER_SIZE)),
+        };
+        freighter
+            .observe(on_freighter_damage)
+            .observe(on_freighter_destroyed);
+    }
+}
+
+// The freighter flies straight to its target, and stops there.
+fn freighter_movement_system(
+    time: Res<Time>,
+    mut freighters: Query<(&mut Freighter, &Position, &mut LinearVelocity)>,
+) {
+    for (mut freighter, position, mut linvel) in freighters.iter_mut() {
+        freighter.hit_cooldown.tick(time.delta());
+        let offset = freighter.target - position.0;
+        if offset.length() < freighter.speed * time.delta_secs() {
+            freighter.arrived = true;
+            linvel.0 = Vec2::ZERO;
+        } else {
+            linvel.0 = offset.normalize() * freighter.speed;
+        }
+    }
+}
+
+fn freighter_asteroid_collision(
+    mut commands: Commands,
+    freighters: Query<(Entity, &CollidingEntities), With<Freighter>>,
+    asteroids: Query<&Transform, With<Asteroid>>,
+) {
+    for (freighter, targets) in freighters.iter() {
+        for target in targets.iter() {
+            if let Ok(asteroid_transform) = asteroids.get(*target) {
+                commands.trigger(Damage {
+                    entity: freighter,
+                    source: Some(*target),
+                    amount: 1,
+                    kind: DamageKind::Collision,
+                    projectile: None,
+                    contact: asteroid_transform.translation.truncate(),
+                });
+            }
+        }
+    }
+}
+
+fn on_freighter_damage(
+    damage: On<Damage>,
+    mut commands: Commands,
+    mut explosion_spawn_events: MessageWriter<SpawnExplosionMessage>,
+    mut freighters: Query<(&mut Freighter, &mut Health, &Transform)>,
+) {
+    let (mut freighter, mut health, transform) = freighters
+        .get_mut(damage.entity)
+        .expect("Missing Freighter, Health and Transform on damage trigger");
+    if !freighter.hit_cooldown.is_finished() {
+        return;
+    }
+    freighter.hit_cooldown.reset();
+    if health.damage(damage.amount) {
+        commands.trigger(Destroyed {
+            entity: damage.entity,
+            source: damage.source,
+            kind: damage.kind,
+            position: transform.translation.truncate(),
+        });
+    } else {
+        explosion_spawn_events.write(SpawnExplosionMessage {
+            kind: ExplosionKind::ShipContact,
+            x: damage.contact.x,
+            y: damage.contact.y,
+        });
+    }
+}
+
+fn on_freighter_destroyed(
+    destroyed: On<Destroyed>,
+    mut commands: Commands,
+    mut explosion_spawn_events: MessageWriter<SpawnExplosionMessage>,
+) {
+    explosion_spawn_events.write(SpawnExplosionMessage {
+        kind: ExplosionKind::ShipDead,
+        x: destroyed.position.x,
+        y: destroyed.position.y,
+    });
+    commands.entity(destroyed.entity).despawn();
+}
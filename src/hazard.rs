@@ -0,0 +1,318 @@
+use std::f32::consts::TAU;
+
+use serde::{Deserialize, Serialize};
+
+use crate::prelude::*;
+
+// Description of a gravity well, as found in wave or level data.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GravityWellDefinition {
+    pub position: Vec2,
+    // Acceleration, in units/s², applied at a distance of 1 unit.
+    // It decreases with the inverse square of the distance.
+    pub strength: f32,
+    // Bodies entering the core are destroyed
+    pub core_radius: f32,
+}
+
+// A point of inverse-square attraction: a gravity well or, with a large core, a black hole.
+#[derive(Component, Debug)]
+pub struct GravityWell {
+    pub strength: f32,
+    pub core_radius: f32,
+    // Wave that brought the well, `None` for the wells of the level itself
+    pub wave: Option<u32>,
+}
+impl GravityWell {
+    // Acceleration applied to a body at `position` by a well located at `center`.
+    // Inside the core the acceleration is capped at its core boundary value.
+    pub fn acceleration(&self, center: Vec2, position: Vec2) -> Vec2 {
+        let offset = center - position;
+        let distance = offset.length().max(self.core_radius).max(1.0);
+        offset.normalize_or_zero() * self.strength / (distance * distance)
+    }
+}
+
+#[derive(Message)]
+pub struct GravityWellSpawnMessage {
+    pub definition: GravityWellDefinition,
+    pub wave: Option<u32>,
+}
+
+pub struct HazardPlugin;
+
+impl Plugin for HazardPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_message::<GravityWellSpawnMessage>()
+            .add_systems(
+                FixedUpdate,
+                (spawn_gravity_well, gravity_well_pull, gravity_well_core)
+                    .chain()
+                    .run_if(in_state(GameState::Running)),
+            )
+            .add_systems(Update, draw_gravity_well.run_if(in_state(AppState::Game)));
+    }
+}
+
+fn spawn_gravity_well(
+    mut commands: Commands,
+    mut spawn_events: MessageReader<GravityWellSpawnMessage>,
+) {
+    for event in spawn_events.read() {
+        commands.spawn((
+            Name::new("GravityWell"),
+            Transform::from_translation(event.definition.position.extend(0.5)),
+            GravityWell {
+                strength: event.definition.strength,
+                core_radius: event.definition.core_radius,
+                wave: event.wave,
+            },
+            DespawnOnExit(AppState::Game),
+        ));
+    }
+}
+
+// Every dynamic body, ship, asteroids and lasers, is pulled toward every well.
+// The pull is an acceleration, so it is applied directly to the velocity whatever the body mass.
+fn gravity_well_pull(
+    time: Res<Time>,
+    wells: Query<(&GravityWell, &Transform)>,
+    mut bodies: Query<(&RigidBody, &Position, &mut LinearVelocity)>,
+) {
+    for (well, well_transform) in wells.iter() {
+        let center = well_transform.translation.truncate();
+        for (rigid_body, position, mut linvel) in bodies.iter_mut() {
+            if rigid_body.is_dynamic() {
+                linvel.0 += well.acceleration(center, position.0) * time.delta_secs();
+            }
+        }
+    }
+}
+
+// Bodies reaching the core of a well are swallowed.
+// Ship and asteroids are destroyed outright, whatever their shield or invincibility, lasers simply vanish.
+fn gravity_well_core(
+    mut commands: Commands,
+    wells: Query<(Entity, &GravityWell, &Transform)>,
+    targets: Query<(Entity, &Position), Or<(With<Ship>, With<Asteroid>)>>,
+    lasers: Query<(Entity, &Position), With<Laser>>,
+) {
+    for (well_entity, well, well_transform) in wells.iter() {
+        let center = well_transform.translation.truncate();
+        for (entity, position) in targets.iter() {
+            if position.0.distance(center) < well.core_radius {
+                commands.trigger(Destroyed {
+                    entity,
+                    source: Some(well_entity),
+                    kind: DamageKind::Hazard,
+                    position: position.0,
+                });
+            }
+        }
+        for (entity, position) in lasers.iter() {
+            if position.0.distance(center) < well.core_radius {
+                commands.entity(entity).despawn();
+            }
+        }
+    }
+}
+
+// The well distortion is suggested by rings shrinking toward a dark core.
+fn draw_gravity_well(
+    time: Res<Time<Virtual>>,
+    mut gizmos: Gizmos,
+    wells: Query<(&GravityWell, &Transform)>,
+) {
+    const RINGS: usize = 4;
+    const RING_SPREAD: f32 = 4.0;
+    for (well, transform) in wells.iter() {
+        let center = transform.translation.truncate();
+        gizmos.circle_2d(center, well.core_radius, Color::srgb(0.3, 0.0, 0.5));
+        let phase = (time.elapsed_secs() * 0.5).fract();
+        for i in 0..RINGS {
+            let t = (i as f32 + 1.0 - phase) / RINGS as f32;
+            let radius = well.core_radius * (1.0 + RING_SPREAD * t);
+            let alpha = 0.6 * (1.0 - t);
+            gizmos
+                .circle_2d(center, radius, Color::srgba(0.6, 0.3, 1.0, alpha))
+                .resolution(48);
+            // A few spokes turning with the rings, giving a swirl to the distortion
+            let angle = phase * TAU + i as f32;
+            gizmos.line_2d(
+                center + Vec2::from_angle(angle) * radius,
+                center + Vec2::from_angle(angle + 0.4) * radius * 0.8,
+                Color::srgba(0.6, 0.3, 1.0, alpha),
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use bevy::{mesh::MeshPlugin, scene::ScenePlugin, time::TimeUpdateStrategy};
+
+    use super::*;
+
+    const TIMESTEP: f64 = 1.0 / 64.0;
+
+    fn create_app() -> App {
+        let mut app = App::new();
+        app.add_plugins((
+            MinimalPlugins,
+            TransformPlugin,
+            AssetPlugin::default(),
+            ScenePlugin,
+            MeshPlugin,
+            PhysicsPlugins::default(),
+        ))
+        .insert_resource(Gravity(Vec2::ZERO))
+        .insert_resource(Time::<Fixed>::from_seconds(TIMESTEP))
+        .insert_resource(TimeUpdateStrategy::ManualDuration(Duration::from_secs_f64(
+            TIMESTEP,
+        )))
+        .add_message::<SpawnExplosionMessage>()
+        .add_message::<CameraEffectMessage>()
+        .add_systems(FixedUpdate, (gravity_well_pull, gravity_well_core).chain())
+        // Stands for the asteroid and ship observers, destroyed bodies are despawned
+        .add_observer(|destroyed: On<Destroyed>, mut commands: Commands| {
+            commands.entity(destroyed.entity).despawn();
+        });
+        app.finish();
+        app.cleanup();
+        app
+    }
+
+    fn spawn_well(app: &mut App, position: Vec2, strength: f32, core_radius: f32) {
+        app.world_mut().spawn((
+            Transform::from_translation(position.extend(0.5)),
+            GravityWell {
+                strength,
+                core_radius,
+                wave: None,
+            },
+        ));
+    }
+
+    fn spawn_body(app: &mut App, position: Vec2, linvel: Vec2) -> Entity {
+        app.world_mut()
+            .spawn((
+                Transform::from_translation(position.extend(1.0)),
+                Asteroid {
+                    size: AsteroidSize::Small,
+                    material: AsteroidMaterial::Rock,
+                },
+                Health::new(1),
+                RigidBody::Dynamic,
+                Collider::circle(5.0),
+                LinearVelocity(linvel),
+            ))
+            .id()
+    }
+
+    // A ship protected by its shield charges, and invincible when `invincible` is set
+    fn spawn_ship(app: &mut App, position: Vec2, invincible: bool) -> Entity {
+        let mut invincible_timer = Timer::from_seconds(1.0, TimerMode::Once);
+        if !invincible {
+            invincible_timer.tick(invincible_timer.duration());
+        }
+        app.world_mut()
+            .spawn((
+                Transform::from_translation(position.extend(1.0)),
+                Ship {
+                    rotation_speed: 3.0,
+                    thrust: 300000.0,
+                    player_id: 1,
+                    invincible_timer,
+                    invincible_time_secs: 0.0,
+                    linear_dampening: 0.4,
+                    angular_dampening: 0.1,
+                    radius: 13.0,
+                    shield: 3,
+                    shield_capacity: 3,
+                },
+                Health::new(3),
+                RigidBody::Dynamic,
+                Collider::circle(13.0),
+                LinearVelocity::ZERO,
+            ))
+            .observe(on_ship_damage)
+            .id()
+    }
+
+    fn step(app: &mut App, steps: u32) {
+        for _ in 0..steps {
+            app.update();
+        }
+    }
+
+    #[test]
+    fn trajectory_bends_toward_well() {
+        let mut app = create_app();
+        spawn_well(&mut app, Vec2::ZERO, 2_000_000.0, 20.0);
+        // Passing 150 units above the well, from left to right
+        let body = spawn_body(&mut app, Vec2::new(-400.0, 150.0), Vec2::new(400.0, 0.0));
+        step(&mut app, 128);
+        let position = app.world().get::<Position>(body).unwrap().0;
+        let linvel = app.world().get::<LinearVelocity>(body).unwrap().0;
+        assert!(
+            position.x > 400.0,
+            "body has not passed the well: {}",
+            position
+        );
+        assert!(
+            linvel.y < 0.0,
+            "velocity not bent toward the well: {}",
+            linvel
+        );
+        assert!(
+            position.y < 150.0,
+            "position not bent toward the well: {}",
+            position
+        );
+    }
+
+    #[test]
+    fn pull_follows_inverse_square_falloff() {
+        let mut app = create_app();
+        spawn_well(&mut app, Vec2::ZERO, 1_000_000.0, 20.0);
+        let near = spawn_body(&mut app, Vec2::new(100.0, 0.0), Vec2::ZERO);
+        let far = spawn_body(&mut app, Vec2::new(0.0, -200.0), Vec2::ZERO);
+        step(&mut app, 8);
+        let near_linvel = app.world().get::<LinearVelocity>(near).unwrap().0;
+        let far_linvel = app.world().get::<LinearVelocity>(far).unwrap().0;
+        // Both are pulled straight toward the center
+        assert!(near_linvel.x < 0.0 && near_linvel.y.abs() < 1e-3);
+        assert!(far_linvel.y > 0.0 && far_linvel.x.abs() < 1e-3);
+        // Twice as far, a quarter of the pull, the bodies barely moved in a few steps
+        let ratio = near_linvel.length() / far_linvel.length();
+        assert!((ratio - 4.0).abs() < 0.1, "pull ratio {} is not 4", ratio);
+    }
+
+    #[test]
+    fn body_destroyed_inside_core() {
+        let mut app = create_app();
+        spawn_well(&mut app, Vec2::ZERO, 2_000_000.0, 20.0);
+        let body = spawn_body(&mut app, Vec2::new(-300.0, 0.0), Vec2::new(200.0, 0.0));
+        let mut steps = 0;
+        while let Some(position) = app.world().get::<Position>(body).map(|position| position.0) {
+            assert!(steps < 256, "body never reached the core");
+            // Still alive, so outside the core at the previous check
+            assert!(position.x < 0.0, "body went through the core: {}", position);
+            step(&mut app, 1);
+            steps += 1;
+        }
+    }
+
+    #[test]
+    fn protected_ships_destroyed_inside_core() {
+        let mut app = create_app();
+        spawn_well(&mut app, Vec2::ZERO, 2_000_000.0, 40.0);
+        let shielded = spawn_ship(&mut app, Vec2::new(10.0, 0.0), false);
+        let invincible = spawn_ship(&mut app, Vec2::new(-10.0, 0.0), true);
+        step(&mut app, 4);
+        assert!(app.world().get_entity(shielded).is_err());
+        assert!(app.world().get_entity(invincible).is_err());
+    }
+}
@@ -0,0 +1,212 @@
+use serde::{Deserialize, Serialize};
+
+use crate::prelude::*;
+
+pub const HIGH_SCORES_SAVE_FILE: &str = "highscores.ron";
+const MAX_HIGH_SCORES: usize = 10;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HighScoreEntry {
+    pub score: u32,
+    pub wave: u32,
+    #[serde(default)]
+    pub mode: GameMode,
+    // Duration of the run in seconds
+    #[serde(default)]
+    pub time: f32,
+    // Name of the played level, `None` for the endless arena
+    pub level: Option<String>,
+    pub mutators: Vec<Mutator>,
+}
+
+// Best runs of each mode, best first, persisted in the save directory.
+#[derive(Debug, Default, Resource, Serialize, Deserialize)]
+pub struct HighScores {
+    pub entries: Vec<HighScoreEntry>,
+    // Rank of the last finished run, if it entered the table
+    #[serde(skip)]
+    pub last_rank: Option<usize>,
+}
+impl HighScores {
+    // Entries of a mode, best first
+    pub fn mode_entries(&self, mode: GameMode) -> impl Iterator<Item = &HighScoreEntry> {
+        self.entries.iter().filter(move |entry| entry.mode == mode)
+    }
+    // Insert the entry at its rank among the entries of its mode,
+    // returns this rank if the entry made it into the table.
+    pub fn insert(&mut self, entry: HighScoreEntry) -> Option<usize> {
+        let mode = entry.mode;
+        let rank = self
+            .mode_entries(mode)
+            .filter(|other| !mode.is_better(&entry, other))
+            .count();
+        if rank >= MAX_HIGH_SCORES {
+            return None;
+        }
+        let index = self
+            .entries
+            .iter()
+            .position(|other| other.mode == mode && mode.is_better(&entry, other))
+            .unwrap_or(self.entries.len());
+        self.entries.insert(index, entry);
+        // Drop the entries of the mode beyond the table size
+        let mut count = 0;
+        self.entries.retain(|other| {
+            if other.mode != mode {
+                return true;
+            }
+            count += 1;
+            count <= MAX_HIGH_SCORES
+        });
+        Some(rank)
+    }
+}
+
+pub struct HighScorePlugin;
+
+impl Plugin for HighScorePlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(load_save::<HighScores>(HIGH_SCORES_SAVE_FILE))
+            .add_systems(OnEnter(GameState::Over), record_high_score);
+    }
+}
+
+// Campaign missions and editor test plays do not enter the table,
+// nor clear field runs that did not clear the field.
+pub fn record_high_score(
+    mut high_scores: ResMut<HighScores>,
+    arena: Res<Arena>,
+    selected_level: Res<SelectedLevel>,
+    mutators: Res<Mutators>,
+    mode: Res<SelectedMode>,
+    clock: Res<ModeClock>,
+    mission: Option<Res<ActiveMission>>,
+    playtest: Option<Res<EditorPlaytest>>,
+) {
+    high_scores.last_rank = None;
+    if mission.is_some() || playtest.is_some() {
+        return;
+    }
+    let finished = match mode.0 {
+        GameMode::ClearField => clock.cleared,
+        _ => arena.score > 0,
+    };
+    if !finished {
+        return;
+    }
+    high_scores.last_rank = high_scores.insert(HighScoreEntry {
+        score: arena.score,
+        wave: arena.wave,
+        mode: mode.0,
+        time: clock.elapsed,
+        level: selected_level.0.as_ref().map(|level| level.name.clone()),
+        mutators: mutators.0.clone(),
+    });
+    if high_scores.last_rank.is_some() {
+        write_save(HIGH_SCORES_SAVE_FILE, &*high_scores);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(mode: GameMode, score: u32, time: f32) -> HighScoreEntry {
+        HighScoreEntry {
+            score,
+            wave: 1,
+            mode,
+            time,
+            level: None,
+            mutators: vec![],
+        }
+    }
+
+    fn scores(high_scores: &HighScores, mode: GameMode) -> Vec<u32> {
+        high_scores
+            .mode_entries(mode)
+            .map(|entry| entry.score)
+            .collect()
+    }
+
+    #[test]
+    fn insert_keeps_entries_best_first() {
+        let mut high_scores = HighScores::default();
+        assert_eq!(
+            high_scores.insert(entry(GameMode::Classic, 30, 0.0)),
+            Some(0)
+        );
+        assert_eq!(
+            high_scores.insert(entry(GameMode::Classic, 50, 0.0)),
+            Some(0)
+        );
+        assert_eq!(
+            high_scores.insert(entry(GameMode::Classic, 10, 0.0)),
+            Some(2)
+        );
+        assert_eq!(
+            high_scores.insert(entry(GameMode::Classic, 40, 0.0)),
+            Some(1)
+        );
+        // A tie ranks after the entry already in the table
+        assert_eq!(
+            high_scores.insert(entry(GameMode::Classic, 30, 0.0)),
+            Some(3)
+        );
+        assert_eq!(
+            scores(&high_scores, GameMode::Classic),
+            vec![50, 40, 30, 30, 10]
+        );
+        // Clear field runs are ranked on their time, the lowest first
+        high_scores.insert(entry(GameMode::ClearField, 0, 90.0));
+        assert_eq!(
+            high_scores.insert(entry(GameMode::ClearField, 0, 60.0)),
+            Some(0)
+        );
+        let times = high_scores
+            .mode_entries(GameMode::ClearField)
+            .map(|entry| entry.time)
+            .collect::<Vec<_>>();
+        assert_eq!(times, vec![60.0, 90.0]);
+    }
+
+    #[test]
+    fn table_is_truncated_per_mode() {
+        let mut high_scores = HighScores::default();
+        high_scores.insert(entry(GameMode::TimeAttack, 5, 0.0));
+        for score in 1..=12 {
+            high_scores.insert(entry(GameMode::Classic, score, 0.0));
+        }
+        assert_eq!(
+            scores(&high_scores, GameMode::Classic),
+            (3..=12).rev().collect::<Vec<_>>()
+        );
+        // Too low to enter a full table
+        assert_eq!(high_scores.insert(entry(GameMode::Classic, 2, 0.0)), None);
+        assert_eq!(
+            high_scores.insert(entry(GameMode::Classic, 8, 0.0)),
+            Some(5)
+        );
+        assert_eq!(high_scores.mode_entries(GameMode::Classic).count(), 10);
+        assert_eq!(scores(&high_scores, GameMode::Classic).last(), Some(&4));
+        // Entries of the other modes are kept
+        assert_eq!(scores(&high_scores, GameMode::TimeAttack), vec![5]);
+    }
+
+    #[test]
+    fn entries_keep_their_mutators() {
+        let mut high_scores = HighScores::default();
+        let mutators = vec![Mutator::FastAsteroids, Mutator::OneLife];
+        high_scores.insert(HighScoreEntry {
+            mutators: mutators.clone(),
+            ..entry(GameMode::Classic, 100, 0.0)
+        });
+        high_scores.insert(entry(GameMode::Classic, 200, 0.0));
+        assert_eq!(high_scores.entries[1].mutators, mutators);
+        assert!(high_scores.entries[0].mutators.is_empty());
+        // And through the save file
+        let content = ron::to_string(&high_scores).unwrap();
+        let loaded = ron::from_str::<HighScores>(&content).unwrap();
+        assert_eq!(loaded.entries[1].mutators, mutators);
+    }
+}
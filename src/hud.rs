@@ -5,6 +5,10 @@ use crate::prelude::*;
 #[derive(Component)]
 pub struct UiScore {}
 #[derive(Component)]
+pub struct UiMultiplier {}
+#[derive(Component)]
+pub struct UiTimer {}
+#[derive(Component)]
 pub struct UiLife {
     pub min: u32,
 }
@@ -14,13 +18,19 @@ impl Plugin for HudPlugin {
     fn build(&self, app: &mut App) {
         app.add_systems(
             Update,
-            (hud_score_system, hud_life_system).run_if(in_state(GameState::Running)),
+            (
+                hud_score_system,
+                hud_multiplier_system,
+                hud_life_system,
+                hud_timer_system,
+            )
+                .run_if(in_state(GameState::Running)),
         )
         .add_systems(OnEnter(GameState::Setup), hud_spawn);
     }
 }
 
-fn hud_spawn(mut commands: Commands, assets: ResMut<UiAssets>) {
+fn hud_spawn(mut commands: Commands, assets: ResMut<UiAssets>, mode: Res<SelectedMode>) {
     commands.spawn((
         Node {
             position_type: PositionType::Absolute,
@@ -32,27 +42,75 @@ fn hud_spawn(mut commands: Commands, assets: ResMut<UiAssets>) {
             ..default()
         },
         DespawnOnExit(AppState::Game),
-        children![(
-            Text::new("0"),
-            TextFont {
-                font: assets.font.clone(),
-                font_size: 50.0,
-                ..default()
-            },
-            TextColor(Color::srgb_u8(0x00, 0xAA, 0xAA)),
-            TextLayout::new_with_justify(Justify::Right),
-            Node {
-                margin: UiRect {
-                    left: Val::Px(10.0),
-                    right: Val::Px(10.0),
-                    top: Val::Px(10.0),
-                    bottom: Val::Px(10.0),
+        children![
+            (
+                Text::new(""),
+                TextFont {
+                    font: assets.font.clone(),
+                    font_size: 25.0,
+                    ..default()
                 },
+                TextColor(Color::srgb_u8(0xF8, 0xE4, 0x73)),
+                Node {
+                    margin: UiRect {
+                        left: Val::Px(10.0),
+                        right: Val::Px(0.0),
+                        top: Val::Px(30.0),
+                        bottom: Val::Px(10.0),
+                    },
+                    ..default()
+                },
+                UiMultiplier {},
+            ),
+            (
+                Text::new("0"),
+                TextFont {
+                    font: assets.font.clone(),
+                    font_size: 50.0,
+                    ..default()
+                },
+                TextColor(Color::srgb_u8(0x00, 0xAA, 0xAA)),
+                TextLayout::new_with_justify(Justify::Right),
+                Node {
+                    margin: UiRect {
+                        left: Val::Px(10.0),
+                        right: Val::Px(10.0),
+                        top: Val::Px(10.0),
+                        bottom: Val::Px(10.0),
+                    },
+                    ..default()
+                },
+                UiScore {},
+            )
+        ],
+    ));
+    // Mode timer, centered at the top, only for the timed modes
+    if mode.0 != GameMode::Classic {
+        commands.spawn((
+            Node {
+                position_type: PositionType::Absolute,
+                width: Val::Percent(100.0),
+                align_items: AlignItems::FlexStart,
+                justify_content: JustifyContent::Center,
                 ..default()
             },
-            UiScore {},
-        )],
-    ));
+            DespawnOnExit(AppState::Game),
+            children![(
+                Text::new(""),
+                TextFont {
+                    font: assets.font.clone(),
+                    font_size: 40.0,
+                    ..default()
+                },
+                TextColor(Color::srgb_u8(0xF8, 0xE4, 0x73)),
+                Node {
+                    margin: UiRect::all(Val::Px(10.0)),
+                    ..default()
+                },
+                UiTimer {},
+            )],
+        ));
+    }
     // Life counters
     // Not kept in 'GameOver' state, simplifying last counter removal.
     let ship_life_image = assets.ship_life.clone();
@@ -67,7 +125,7 @@ fn hud_spawn(mut commands: Commands, assets: ResMut<UiAssets>) {
             ..default()
         },
         DespawnOnExit(AppState::Game),
-        Children::spawn(SpawnIter((1..(START_LIFE + 1)).map(move |i| {
+        Children::spawn(SpawnIter((1..(MAX_LIFE + 1)).map(move |i| {
             (
                 ImageNode::new(ship_life_image.clone()),
                 Node {
@@ -92,22 +150,51 @@ fn hud_score_system(arena: Res<Arena>, mut query: Query<&mut Text, With<UiScore>
         }
     }
 }
+fn hud_multiplier_system(
+    tracker: Res<ScoreTracker>,
+    mut query: Query<&mut Text, With<UiMultiplier>>,
+) {
+    if tracker.is_changed() {
+        for mut text in query.iter_mut() {
+            let multiplier = tracker.multiplier();
+            **text = if multiplier > 1 {
+                format!("x{}", multiplier)
+            } else {
+                String::new()
+            };
+        }
+    }
+}
+fn hud_timer_system(
+    clock: Res<ModeClock>,
+    mode: Res<SelectedMode>,
+    mut query: Query<&mut Text, With<UiTimer>>,
+) {
+    if let Some(secs) = clock.display_time(mode.0) {
+        for mut text in query.iter_mut() {
+            let time = format_time(secs);
+            if **text != time {
+                **text = time;
+            }
+        }
+    }
+}
 fn hud_life_system(
     mut commands: Commands,
-    ship_query: Query<&Ship, Changed<Ship>>,
-    mut uilife_query: Query<(Entity, &UiLife)>,
+    ship_query: Query<(&Ship, &Health), Changed<Health>>,
+    uilife_query: Query<(Entity, &UiLife)>,
 ) {
-    let mut life = 0;
-    for ship in ship_query.iter() {
+    for (ship, health) in ship_query.iter() {
         if ship.player_id == 1 {
-            life = ship.life;
+            for (entity, uilife) in uilife_query.iter() {
+                commands
+                    .entity(entity)
+                    .insert(if health.current >= uilife.min {
+                        Visibility::Visible
+                    } else {
+                        Visibility::Hidden
+                    });
+            }
         }
     }
-    for (entity, uilife) in uilife_query.iter_mut() {
-        commands.entity(entity).insert(if life >= uilife.min {
-            Visibility::Visible
-        } else {
-            Visibility::Hidden
-        });
-    }
 }
@@ -0,0 +1,334 @@
+use bevy::asset::{AssetLoader, LoadContext, io::Reader};
+use bevy::ecs::spawn::{Spawn, SpawnIter};
+use serde::{Deserialize, Serialize};
+
+use crate::prelude::*;
+
+// Hull files shipped with the game, proposed in the hangar in this order.
+pub const HULL_FILES: [&str; 3] = [
+    "hulls/fighter.hull.ron",
+    "hulls/interceptor.hull.ron",
+    "hulls/gunship.hull.ron",
+];
+// Radius of the reference hull, the vector shape is scaled against it.
+const BASE_RADIUS: f32 = 13.5;
+const STAT_BAR_WIDTH: f32 = 200.0;
+// The hull sprite is previewed in the hangar at this multiple of its size in game
+const HANGAR_PREVIEW_SCALE: f32 = 3.0;
+
+// Ship characteristics, loaded from a `.hull.ron` file.
+#[derive(Asset, TypePath, Debug, Clone, Serialize, Deserialize)]
+pub struct Hull {
+    pub name: String,
+    // Sprite image path, drawn at `sprite_size`
+    pub sprite: String,
+    pub sprite_size: Vec2,
+    // Rotation speed in rad/s
+    pub rotation_speed: f32,
+    // Thrust in N
+    pub thrust: f32,
+    // Collider radius
+    pub radius: f32,
+    // Lives at start, at most `MAX_LIFE`
+    pub life: u32,
+    pub weapon: WeaponKind,
+    // Stardust cost to unlock the hull, 0 when available from the start, see `Progression`
+    #[serde(default)]
+    pub unlock_price: u32,
+}
+impl Default for Hull {
+    fn default() -> Self {
+        Hull {
+            name: "Fighter".into(),
+            sprite: "playerShip2_red.png".into(),
+            sprite_size: Vec2::new(30.0, 20.0),
+            rotation_speed: 3.0,
+            thrust: 300000.0,
+            radius: BASE_RADIUS,
+            life: START_LIFE,
+            weapon: WeaponKind::Single,
+            unlock_price: 0,
+        }
+    }
+}
+
+// The statistics compared in the hangar, each shown as a bar.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum HullStat {
+    Rotation,
+    Thrust,
+    Size,
+    Life,
+}
+impl HullStat {
+    pub const ALL: [HullStat; 4] = [
+        HullStat::Rotation,
+        HullStat::Thrust,
+        HullStat::Size,
+        HullStat::Life,
+    ];
+    // `Locale` key of the name
+    pub fn key(&self) -> &'static str {
+        match self {
+            HullStat::Rotation => "stat-rotation",
+            HullStat::Thrust => "stat-thrust",
+            HullStat::Size => "stat-size",
+            HullStat::Life => "stat-life",
+        }
+    }
+    // Fraction of the bar filled for this hull
+    pub fn fraction(&self, hull: &Hull) -> f32 {
+        let fraction = match self {
+            HullStat::Rotation => hull.rotation_speed / 5.0,
+            HullStat::Thrust => hull.thrust / 500000.0,
+            HullStat::Size => hull.radius / 20.0,
+            HullStat::Life => hull.life as f32 / MAX_LIFE as f32,
+        };
+        fraction.clamp(0.0, 1.0)
+    }
+}
+
+#[derive(Default)]
+pub struct HullLoader;
+
+impl AssetLoader for HullLoader {
+    type Asset = Hull;
+    type Settings = ();
+    type Error = BevyError;
+    async fn load(
+        &self,
+        reader: &mut dyn Reader,
+        _settings: &(),
+        _load_context: &mut LoadContext<'_>,
+    ) -> Result<Hull, BevyError> {
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes).await?;
+        Ok(ron::de::from_bytes::<Hull>(&bytes)?)
+    }
+    fn extensions(&self) -> &[&str] {
+        &["hull.ron"]
+    }
+}
+
+// Handles of all the known hulls, loaded at startup
+#[derive(Debug, Resource)]
+pub struct HullAssets {
+    pub hulls: Vec<Handle<Hull>>,
+}
+
+// The hull flown in the next games, kept until another one is picked in the hangar.
+#[derive(Debug, Resource, Default)]
+pub struct SelectedHull(pub Hull);
+
+// The hangar menu, listing the loaded hulls in the order of their entries, then back to the menu.
+#[derive(Component)]
+pub struct HangarMenu {
+    pub hulls: Vec<Hull>,
+}
+#[derive(Component)]
+pub struct HangarStatBar(HullStat);
+#[derive(Component)]
+pub struct HangarWeaponText;
+#[derive(Component)]
+pub struct HangarPreview;
+
+pub struct HullPlugin;
+
+impl Plugin for HullPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_asset::<Hull>()
+            .init_asset_loader::<HullLoader>()
+            .init_resource::<SelectedHull>()
+            .add_systems(OnEnter(AppState::Setup), load_hulls)
+            .add_systems(OnEnter(AppState::Hangar), spawn_hangar_menu)
+            .add_systems(
+                Update,
+                hangar_stats_system.run_if(in_state(AppState::Hangar)),
+            );
+    }
+}
+
+fn load_hulls(mut commands: Commands, asset_server: Res<AssetServer>) {
+    let hulls = HULL_FILES
+        .iter()
+        .map(|path| asset_server.load(*path))
+        .collect();
+    commands.insert_resource(HullAssets { hulls });
+}
+
+// Only the hulls that have been successfully loaded are listed, locked ones can not be picked
+// but their stats can still be compared.
+// Stats of the highlighted hull are displayed on the right.
+fn spawn_hangar_menu(
+    mut commands: Commands,
+    assets: ResMut<UiAssets>,
+    locale: Res<Locale>,
+    hull_assets: Res<HullAssets>,
+    hull_library: Res<Assets<Hull>>,
+    selected_hull: Res<SelectedHull>,
+    progression: Res<Progression>,
+) {
+    let hulls = hull_assets
+        .hulls
+        .iter()
+        .filter_map(|handle| hull_library.get(handle).cloned())
+        .collect::<Vec<_>>();
+    let mut entries = hulls
+        .iter()
+        .map(|hull| {
+            if progression.hull_unlocked(hull) {
+                let selected = hull.clone();
+                MenuEntry::button(
+                    hull.name.clone(),
+                    MenuCommand::run(move |world| {
+                        world.resource_mut::<SelectedHull>().0 = selected.clone();
+                    })
+                    .then(MenuCommand::Back),
+                )
+            } else {
+                MenuEntry::label(locale.format("hangar-locked", &[("hull", &hull.name)]))
+            }
+        })
+        .collect::<Vec<_>>();
+    entries.push(MenuEntry::button(
+        "menu-menu",
+        MenuCommand::Open(AppState::Menu),
+    ));
+    let selected_id = hulls
+        .iter()
+        .position(|hull| hull.name == selected_hull.0.name)
+        .unwrap_or(0) as i32;
+    let entity = MenuHandler {
+        main_text: "menu-hangar".into(),
+        main_text_color: MenuTone::Title,
+        main_text_blink: false,
+        selected_id,
+        entries,
+    }
+    .spawn(&mut commands, assets.font.clone());
+    commands
+        .entity(entity)
+        .insert((HangarMenu { hulls }, DespawnOnExit(AppState::Hangar)));
+
+    let font = assets.font_fira.clone();
+    let rows = HullStat::ALL.map(|stat| {
+        (
+            Node {
+                align_items: AlignItems::Center,
+                margin: UiRect::all(Val::Px(5.0)),
+                ..default()
+            },
+            children![
+                (
+                    Text::new(locale.tr(stat.key())),
+                    TextFont {
+                        font: font.clone(),
+                        font_size: 20.0,
+                        ..default()
+                    },
+                    TextColor(Color::WHITE),
+                    Node {
+                        width: Val::Px(100.0),
+                        ..default()
+                    },
+                ),
+                (
+                    Node {
+                        width: Val::Px(STAT_BAR_WIDTH),
+                        height: Val::Px(14.0),
+                        ..default()
+                    },
+                    BackgroundColor(Color::srgb(0.2, 0.2, 0.2)),
+                    children![(
+                        Node {
+                            width: Val::Percent(0.0),
+                            height: Val::Percent(100.0),
+                            ..default()
+                        },
+                        BackgroundColor(Color::srgb(0.0, 0.7, 0.7)),
+                        HangarStatBar(stat),
+                    )],
+                ),
+            ],
+        )
+    });
+    commands
+        .spawn((
+            Node {
+                position_type: PositionType::Absolute,
+                right: Val::Px(40.0),
+                top: Val::Percent(50.0),
+                flex_direction: FlexDirection::Column,
+                ..default()
+            },
+            DespawnOnExit(AppState::Hangar),
+            Children::spawn((
+                Spawn((
+                    ImageNode::default(),
+                    Node {
+                        align_self: AlignSelf::Center,
+                        margin: UiRect::all(Val::Px(10.0)),
+                        ..default()
+                    },
+                    HangarPreview,
+                )),
+                SpawnIter(rows.into_iter()),
+            )),
+        ))
+        .with_child((
+            Text::new(""),
+            TextFont {
+                font: font.clone(),
+                font_size: 20.0,
+                ..default()
+            },
+            TextColor(Color::WHITE),
+            Node {
+                margin: UiRect::all(Val::Px(5.0)),
+                ..default()
+            },
+            HangarWeaponText,
+        ));
+}
+
+// Preview and bars follow the highlighted hull, and are emptied on the last entry.
+fn hangar_stats_system(
+    menu: Query<(&MenuHandler, &HangarMenu), Changed<MenuHandler>>,
+    locale: Res<Locale>,
+    asset_server: Res<AssetServer>,
+    mut bars: Query<(&HangarStatBar, &mut Node), Without<HangarPreview>>,
+    mut preview: Query<(&mut ImageNode, &mut Node), With<HangarPreview>>,
+    mut weapon_text: Query<&mut Text, With<HangarWeaponText>>,
+) {
+    let Ok((menu, hangar)) = menu.single() else {
+        return;
+    };
+    let hull = hangar.hulls.get(menu.selected_id as usize);
+    for (mut image, mut node) in preview.iter_mut() {
+        let size = hull.map_or(Vec2::ZERO, |hull| hull.sprite_size * HANGAR_PREVIEW_SCALE);
+        if let Some(hull) = hull {
+            image.image = asset_server.load(&hull.sprite);
+        }
+        node.width = Val::Px(size.x);
+        node.height = Val::Px(size.y);
+    }
+    for (bar, mut node) in bars.iter_mut() {
+        let fraction = hull.map_or(0.0, |hull| bar.0.fraction(hull));
+        node.width = Val::Percent(fraction * 100.0);
+    }
+    for mut text in weapon_text.iter_mut() {
+        **text = hull.map_or(String::new(), |hull| {
+            locale.format("hangar-weapon", &[("weapon", &hull.weapon)])
+        });
+    }
+}
+
+// Reference shape scaled to the hull collider
+pub fn hull_vector_shape(hull: &Hull) -> VectorShape {
+    let mut shape = VectorShape::ship();
+    let scale = hull.radius / BASE_RADIUS;
+    for point in shape.points.iter_mut() {
+        *point *= scale;
+    }
+    shape
+}
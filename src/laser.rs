@@ -6,19 +6,37 @@ pub struct LaserSpawnMessage {
     pub transform: Transform,
     // The velocity of the entity emitting the laser
     pub linvel: LinearVelocity,
+    // The entity emitting the laser, credited for the damage it deals
+    pub owner: Entity,
+    // The weapon firing the laser, defining its characteristics
+    pub weapon: WeaponKind,
 }
 
+// Laser specific state, lifetime and range are handled by its `Projectile` component.
 #[derive(Component)]
 pub struct Laser {
-    pub despawn_timer: Timer,
+    pub owner: Entity,
+    // Number of asteroids the laser can still pass through
+    pub pierce: u32,
+    // Asteroids already damaged by this laser, a piercing laser only damages each asteroid once.
+    pub hits: Vec<Entity>,
+    // Laser position at the previous hit detection, start of the next swept segment
+    pub swept_from: Vec2,
+}
+
+// Laser steering toward the nearest asteroid
+#[derive(Component)]
+pub struct Homing {
+    // Maximum turn rate in rad/s
+    pub turn_rate: f32,
 }
 pub struct LaserPlugin;
 
 impl Plugin for LaserPlugin {
     fn build(&self, app: &mut App) {
         app.add_message::<LaserSpawnMessage>().add_systems(
-            Update,
-            (spawn_laser, laser_asteroid_collision, laser_timeout_system)
+            FixedUpdate,
+            (spawn_laser, laser_homing_system, laser_asteroid_collision)
                 .run_if(in_state(GameState::Running)),
         );
     }
@@ -28,82 +46,248 @@ fn spawn_laser(
     mut commands: Commands,
     mut laser_spawn_events: MessageReader<LaserSpawnMessage>,
     handles: Res<SpriteAssets>,
-    audios: Res<AudioAssets>,
+    settings: Res<Settings>,
 ) {
     for spawn_event in laser_spawn_events.read() {
+        let definition = spawn_event.weapon.definition();
         let mut transform = spawn_event.transform;
         // Enforce laser sprite layer
         transform.translation.z = 2.0;
+        // The laser inherits the full velocity of its emitter
         let linvel = LinearVelocity(
-            (spawn_event.linvel.0 * Vec2::Y) + (transform.rotation * Vec3::Y * 500.0).truncate(),
+            spawn_event.linvel.0 + (transform.rotation * Vec3::Y * definition.speed).truncate(),
         );
         let collider = Collider::rectangle(2.5, 10.0);
         // It seems the way laser are spawned, xpbd does not create a ColliderMassProperties.
         // So I add it explicitly to avoid a runtime warning.
         // I did not search why the laser spawning is special.
         let mass_properties = MassPropertiesBundle::from_shape(&collider, 1.0);
-        commands.spawn((
+        let mut laser = commands.spawn((
             Name::new("Laser"),
-            Sprite {
-                image: handles.laser.clone(),
-                custom_size: Some(Vec2::new(5., 20.0)),
-                ..default()
-            },
             transform,
+            Projectile::new(definition.lifetime, definition.range),
             Laser {
-                despawn_timer: Timer::from_seconds(2.0, TimerMode::Once),
+                owner: spawn_event.owner,
+                pierce: definition.pierce,
+                hits: vec![],
+                swept_from: transform.translation.truncate(),
             },
-            CollisionLayers::new(GameLayer::Laser, [GameLayer::Asteroid]),
-            CollidingEntities::default(),
+            CollisionLayers::new(GameLayer::Laser, [GameLayer::Asteroid, GameLayer::Obstacle]),
             RigidBody::Dynamic,
             collider,
             mass_properties,
             linvel,
             Sensor,
-            AudioPlayer(audios.laser_trigger.clone()),
             DespawnOnExit(AppState::Game),
         ));
+        match settings.render_style {
+            RenderStyle::Sprite => laser.insert(Sprite {
+                image: handles.lasers[&spawn_event.weapon].clone(),
+                custom_size: Some(definition.size),
+                ..default()
+            }),
+            RenderStyle::Vector => laser.insert(VectorShape {
+                color: definition.color,
+                ..VectorShape::laser()
+            }),
+        };
+        if definition.homing_turn_rate > 0.0 {
+            laser.insert(Homing {
+                turn_rate: definition.homing_turn_rate,
+            });
+        }
     }
 }
 
+// Homing lasers keep their speed, but rotate their velocity toward the nearest asteroid.
+fn laser_homing_system(
+    time: Res<Time>,
+    mut lasers: Query<(&Homing, &mut LinearVelocity, &mut Transform), Without<Asteroid>>,
+    asteroids: Query<&Transform, With<Asteroid>>,
+) {
+    for (homing, mut linvel, mut transform) in lasers.iter_mut() {
+        let position = transform.translation.truncate();
+        let target = asteroids
+            .iter()
+            .map(|asteroid_transform| asteroid_transform.translation.truncate())
+            .min_by(|a, b| {
+                a.distance_squared(position)
+                    .total_cmp(&b.distance_squared(position))
+            });
+        if let Some(target) = target {
+            let angle = linvel.0.angle_to(target - position);
+            let max_turn = homing.turn_rate * time.delta_secs();
+            linvel.0 = Vec2::from_angle(angle.clamp(-max_turn, max_turn)).rotate(linvel.0);
+            transform.rotation = Quat::from_rotation_z(Vec2::Y.angle_to(linvel.0));
+        }
+    }
+}
+
+// Lasers are fast and thin: checking collider overlaps after each physics step
+// lets them tunnel through small asteroids when the step is long.
+// Instead, the whole segment travelled since the previous check is swept with a ray cast,
+// so every asteroid crossed is hit whatever the frame rate or physics timestep.
 fn laser_asteroid_collision(
     mut commands: Commands,
     mut explosion_spawn_events: MessageWriter<SpawnExplosionMessage>,
-    laser_collisions: Query<(Entity, &CollidingEntities), With<Laser>>,
-    is_asteroid: Query<(), With<Asteroid>>,
-    transforms: Query<&Transform>,
+    spatial_query: SpatialQuery,
+    mut lasers: Query<(Entity, &mut Laser, &Position)>,
+    obstacles: Query<&Obstacle>,
 ) {
-    for (laser, targets) in laser_collisions.iter() {
-        for target in targets.iter() {
+    for (laser_entity, mut laser, position) in lasers.iter_mut() {
+        let origin = laser.swept_from;
+        let segment = position.0 - origin;
+        laser.swept_from = position.0;
+        // When wrapping around the arena edges, the segment is not the travelled path.
+        if segment.length() > ARENA_HEIGHT / 2.0 {
+            continue;
+        }
+        let Ok(direction) = Dir2::new(segment) else {
+            continue;
+        };
+        let filter = SpatialQueryFilter::from_mask([GameLayer::Asteroid, GameLayer::Obstacle])
+            .with_excluded_entities(laser.hits.clone());
+        // Hits are not ordered by distance, all of them are needed to find the nearest ones.
+        let mut hits =
+            spatial_query.ray_hits(origin, direction, segment.length(), u32::MAX, true, &filter);
+        hits.sort_by(|a, b| a.distance.total_cmp(&b.distance));
+        hits.truncate(laser.pierce as usize + 1);
+        for hit in hits {
             // Laser on Asteroid collision
-            // The asteroid is damaged and the laser despawned.
-            // A LaserOnAsteroid explosion VFX is triggered. To simplify code
-            // the VFX is triggered at the laser position and not at the exact contact position.
-            if is_asteroid.contains(*target) {
-                commands.trigger(Damage { entity: *target });
-                let laser_transform = transforms
-                    .get(laser)
-                    .expect("Missing transform for the laser");
+            // The asteroid is damaged and the laser despawned, unless it can still pierce.
+            // A LaserOnAsteroid explosion VFX is triggered at the contact position.
+            let contact = origin + direction * hit.distance;
+            // Walls and stations are indestructible and stop any laser, even piercing ones.
+            if let Ok(obstacle) = obstacles.get(hit.entity)
+                && obstacle.kind != ObstacleKind::Mine
+            {
                 explosion_spawn_events.write(SpawnExplosionMessage {
-                    kind: ExplosionKind::LaserOnAsteroid,
-                    x: laser_transform.translation.x,
-                    y: laser_transform.translation.y,
+                    kind: ExplosionKind::LaserOnObstacle,
+                    x: contact.x,
+                    y: contact.y,
                 });
-                commands.entity(laser).despawn();
+                commands.entity(laser_entity).despawn();
+                break;
+            }
+            commands.trigger(Damage {
+                entity: hit.entity,
+                source: Some(laser.owner),
+                amount: 1,
+                kind: DamageKind::Laser,
+                projectile: Some(laser_entity),
+                contact,
+            });
+            explosion_spawn_events.write(SpawnExplosionMessage {
+                kind: ExplosionKind::LaserOnAsteroid,
+                x: contact.x,
+                y: contact.y,
+            });
+            laser.hits.push(hit.entity);
+            if laser.pierce == 0 {
+                commands.entity(laser_entity).despawn();
+                break;
             }
+            laser.pierce -= 1;
         }
     }
 }
 
-fn laser_timeout_system(
-    mut commands: Commands,
-    time: Res<Time>,
-    mut query: Query<(Entity, &mut Laser)>,
-) {
-    for (entity, mut laser) in query.iter_mut() {
-        laser.despawn_timer.tick(time.delta());
-        if laser.despawn_timer.is_finished() {
-            commands.entity(entity).despawn();
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use bevy::{mesh::MeshPlugin, scene::ScenePlugin, time::TimeUpdateStrategy};
+
+    use super::*;
+
+    // Faster than any weapon with the inherited ship velocity,
+    // a laser travels more than the asteroid diameter in one step at the lowest tick rate.
+    const LASER_SPEED: f32 = 2000.0;
+
+    #[derive(Default, Resource)]
+    struct DamageCount(u32);
+
+    fn create_app(tick_rate: f64) -> App {
+        let mut app = App::new();
+        app.add_plugins((
+            MinimalPlugins,
+            TransformPlugin,
+            AssetPlugin::default(),
+            ScenePlugin,
+            MeshPlugin,
+            PhysicsPlugins::default(),
+        ))
+        .insert_resource(Gravity(Vec2::ZERO))
+        .insert_resource(Time::<Fixed>::from_hz(tick_rate))
+        .insert_resource(TimeUpdateStrategy::ManualDuration(Duration::from_secs_f64(
+            1.0 / tick_rate,
+        )))
+        .init_resource::<DamageCount>()
+        .add_message::<SpawnExplosionMessage>()
+        .add_systems(FixedUpdate, laser_asteroid_collision)
+        .add_observer(|_damage: On<Damage>, mut count: ResMut<DamageCount>| count.0 += 1);
+        app.finish();
+        app.cleanup();
+        app
+    }
+
+    // Same physics as `spawn_laser`, without the sprite assets
+    fn fire(app: &mut App, from: Vec2) -> Entity {
+        let transform = Transform::from_translation(from.extend(2.0));
+        let collider = Collider::rectangle(2.5, 10.0);
+        let mass_properties = MassPropertiesBundle::from_shape(&collider, 1.0);
+        app.world_mut()
+            .spawn((
+                transform,
+                Laser {
+                    owner: Entity::PLACEHOLDER,
+                    pierce: 0,
+                    hits: vec![],
+                    swept_from: from,
+                },
+                CollisionLayers::new(GameLayer::Laser, [GameLayer::Asteroid, GameLayer::Obstacle]),
+                RigidBody::Dynamic,
+                collider,
+                mass_properties,
+                LinearVelocity(Vec2::Y * LASER_SPEED),
+                Sensor,
+            ))
+            .id()
+    }
+
+    #[test]
+    fn laser_hits_small_asteroid_at_every_tick_rate() {
+        for tick_rate in TICK_RATES {
+            let mut app = create_app(tick_rate);
+            // Static, so that it stays in the line of fire after each hit
+            app.world_mut().spawn((
+                Transform::default(),
+                Asteroid {
+                    size: AsteroidSize::Small,
+                    material: AsteroidMaterial::Rock,
+                },
+                CollisionLayers::new(GameLayer::Asteroid, [GameLayer::Laser]),
+                RigidBody::Static,
+                Collider::circle(28. / 2.0),
+            ));
+            app.update();
+            for (shot, x) in [-12.0, -6.0, 0.0, 6.0, 12.0].into_iter().enumerate() {
+                let laser = fire(&mut app, Vec2::new(x, -300.0));
+                // One second of flight is far past the asteroid
+                for _ in 0..tick_rate as u32 {
+                    app.update();
+                    if app.world().get_entity(laser).is_err() {
+                        break;
+                    }
+                }
+                assert_eq!(
+                    app.world().resource::<DamageCount>().0,
+                    shot as u32 + 1,
+                    "shot at x={} missed at {} Hz",
+                    x,
+                    tick_rate
+                );
+            }
         }
     }
 }
@@ -0,0 +1,161 @@
+use bevy::audio::{AddAudioSource, Decodable, Source};
+use core::time::Duration;
+
+use crate::prelude::*;
+
+// Shape of the wave of a synthesized sound.
+#[derive(Debug, Copy, Clone)]
+pub enum Waveform {
+    Sine,
+    Square,
+    Saw,
+    // White noise, sampled and held at the sweep frequency
+    Noise,
+}
+
+// A synthesized laser shot, a tone sweeping from its start to its end frequency while fading out.
+#[derive(Asset, TypePath, Debug, Copy, Clone)]
+pub struct LaserSound {
+    pub waveform: Waveform,
+    // Frequencies in Hz at the start and the end of the shot
+    pub start_frequency: f32,
+    pub end_frequency: f32,
+    // Duration in seconds
+    pub duration: f32,
+}
+
+const LASER_SOUND_SAMPLE_RATE: u32 = 44100;
+const LASER_SOUND_AMPLITUDE: f32 = 0.3;
+
+impl Decodable for LaserSound {
+    type DecoderItem = f32;
+    type Decoder = LaserSoundDecoder;
+
+    fn decoder(&self) -> Self::Decoder {
+        LaserSoundDecoder {
+            sound: *self,
+            sample: 0,
+            phase: 0.0,
+            noise: 0x2545_f491,
+            held: 0.0,
+        }
+    }
+}
+
+pub struct LaserSoundDecoder {
+    sound: LaserSound,
+    // Index of the next sample
+    sample: u32,
+    // Position in the current wave period, in [0, 1)
+    phase: f32,
+    // Xorshift state and current value of the noise waveform
+    noise: u32,
+    held: f32,
+}
+
+impl LaserSoundDecoder {
+    fn total_samples(&self) -> u32 {
+        (self.sound.duration * LASER_SOUND_SAMPLE_RATE as f32) as u32
+    }
+}
+
+impl Iterator for LaserSoundDecoder {
+    type Item = f32;
+
+    fn next(&mut self) -> Option<f32> {
+        let total = self.total_samples();
+        if self.sample >= total {
+            return None;
+        }
+        let progress = self.sample as f32 / total as f32;
+        // Exponential sweep, heard as a steady pitch change
+        let sound = &self.sound;
+        let frequency =
+            sound.start_frequency * (sound.end_frequency / sound.start_frequency).powf(progress);
+        let value = match sound.waveform {
+            Waveform::Sine => (self.phase * std::f32::consts::TAU).sin(),
+            Waveform::Square => {
+                if self.phase < 0.5 {
+                    1.0
+                } else {
+                    -1.0
+                }
+            }
+            Waveform::Saw => 2.0 * self.phase - 1.0,
+            Waveform::Noise => self.held,
+        };
+        self.phase += frequency / LASER_SOUND_SAMPLE_RATE as f32;
+        if self.phase >= 1.0 {
+            self.phase = self.phase.fract();
+            self.noise ^= self.noise << 13;
+            self.noise ^= self.noise >> 17;
+            self.noise ^= self.noise << 5;
+            self.held = self.noise as f32 / u32::MAX as f32 * 2.0 - 1.0;
+        }
+        self.sample += 1;
+        // Short attack to avoid a click, then a quadratic fade out
+        let attack = (progress * 50.0).min(1.0);
+        let envelope = attack * (1.0 - progress).powi(2);
+        Some(value * envelope * LASER_SOUND_AMPLITUDE)
+    }
+}
+
+impl Source for LaserSoundDecoder {
+    fn current_frame_len(&self) -> Option<usize> {
+        Some((self.total_samples() - self.sample) as usize)
+    }
+    fn channels(&self) -> u16 {
+        1
+    }
+    fn sample_rate(&self) -> u32 {
+        LASER_SOUND_SAMPLE_RATE
+    }
+    fn total_duration(&self) -> Option<Duration> {
+        Some(Duration::from_secs_f32(self.sound.duration))
+    }
+}
+
+pub struct LaserSoundPlugin;
+
+impl Plugin for LaserSoundPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_audio_source::<LaserSound>();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sounds_last_their_duration_within_bounds() {
+        for waveform in [
+            Waveform::Sine,
+            Waveform::Square,
+            Waveform::Saw,
+            Waveform::Noise,
+        ] {
+            let sound = LaserSound {
+                waveform,
+                start_frequency: 1000.0,
+                end_frequency: 200.0,
+                duration: 0.25,
+            };
+            let decoder = sound.decoder();
+            assert_eq!(decoder.channels(), 1);
+            assert_eq!(decoder.current_frame_len(), Some(11025));
+            let samples = decoder.collect::<Vec<_>>();
+            assert_eq!(samples.len(), 11025);
+            assert!(
+                samples
+                    .iter()
+                    .all(|sample| sample.abs() <= LASER_SOUND_AMPLITUDE),
+                "{:?} sample out of bounds",
+                waveform
+            );
+            // Fading out, the end of the shot is quieter than its start
+            let peak = |samples: &[f32]| samples.iter().fold(0.0f32, |max, s| max.max(s.abs()));
+            assert!(peak(&samples[10000..]) < peak(&samples[500..1500]));
+        }
+    }
+}
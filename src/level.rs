@@ -0,0 +1,335 @@
+use bevy::asset::{AssetLoader, LoadContext, io::Reader};
+use serde::{Deserialize, Serialize};
+
+use crate::prelude::*;
+
+// Level files shipped with the game, proposed in the level selection menu.
+pub const LEVEL_FILES: [&str; 3] = [
+    "levels/asteroid_belt.level.ron",
+    "levels/space_station.level.ron",
+    "levels/minefield.level.ron",
+];
+// Level written by the editor in the save directory, listed with the others once it has been saved.
+pub const CUSTOM_LEVEL_FILE: &str = "levels/custom.level.ron";
+// Asset path of the custom level, read from the save asset source
+pub const CUSTOM_LEVEL_ASSET: &str = "save://levels/custom.level.ron";
+pub const MINE_RADIUS: f32 = 10.0;
+
+#[derive(Debug, Copy, Clone, PartialEq, Serialize, Deserialize)]
+pub enum ObstacleKind {
+    // Indestructible rectangular wall
+    Wall { size: Vec2 },
+    // Indestructible round structure
+    Station { radius: f32 },
+    // Explodes on contact with the ship, can be shot down
+    Mine,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ObstacleDefinition {
+    pub kind: ObstacleKind,
+    pub position: Vec2,
+    // Rotation in radians
+    #[serde(default)]
+    pub rotation: f32,
+}
+
+// A circular zone damaging the ship while it stays inside
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HazardZoneDefinition {
+    pub position: Vec2,
+    pub radius: f32,
+}
+
+// Layout and asteroid schedule of an arena, loaded from a `.level.ron` file.
+#[derive(Asset, TypePath, Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Level {
+    pub name: String,
+    #[serde(default)]
+    pub player_start: Vec2,
+    // Asteroids enter the arena from one of these points, or from a random edge when empty
+    #[serde(default)]
+    pub asteroid_spawns: Vec<Vec2>,
+    #[serde(default)]
+    pub obstacles: Vec<ObstacleDefinition>,
+    // Gravity wells present during the whole level, waves can add their own
+    #[serde(default)]
+    pub gravity_wells: Vec<GravityWellDefinition>,
+    #[serde(default)]
+    pub hazard_zones: Vec<HazardZoneDefinition>,
+    // Asteroid spawn schedule, procedural endless waves follow the last one
+    #[serde(default)]
+    pub waves: Vec<WaveDefinition>,
+}
+
+#[derive(Default)]
+pub struct LevelLoader;
+
+impl AssetLoader for LevelLoader {
+    type Asset = Level;
+    type Settings = ();
+    type Error = BevyError;
+    async fn load(
+        &self,
+        reader: &mut dyn Reader,
+        _settings: &(),
+        _load_context: &mut LoadContext<'_>,
+    ) -> Result<Level, BevyError> {
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes).await?;
+        Ok(ron::de::from_bytes::<Level>(&bytes)?)
+    }
+    fn extensions(&self) -> &[&str] {
+        &["level.ron"]
+    }
+}
+
+// Handles of all the known levels, loaded at startup
+#[derive(Debug, Resource)]
+pub struct LevelAssets {
+    pub levels: Vec<Handle<Level>>,
+}
+
+// The level of the next game, `None` for the default empty arena.
+#[derive(Debug, Resource, Default)]
+pub struct SelectedLevel(pub Option<Level>);
+
+// Static part of the arena layout
+#[derive(Component)]
+pub struct Obstacle {
+    pub kind: ObstacleKind,
+}
+
+// Circular area damaging the ship while inside, it does not affect asteroids.
+#[derive(Component)]
+pub struct HazardZone {
+    pub radius: f32,
+}
+
+pub struct LevelPlugin;
+
+impl Plugin for LevelPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_asset::<Level>()
+            .init_asset_loader::<LevelLoader>()
+            .init_resource::<SelectedLevel>()
+            .add_systems(OnEnter(AppState::Setup), load_levels)
+            .add_systems(OnEnter(GameState::Setup), spawn_level)
+            .add_systems(
+                FixedUpdate,
+                (ship_mine_collision, hazard_zone_damage).run_if(in_state(GameState::Running)),
+            )
+            .add_systems(Update, draw_hazard_zone.run_if(in_state(AppState::Game)));
+    }
+}
+
+fn load_levels(mut commands: Commands, asset_server: Res<AssetServer>) {
+    let mut levels: Vec<Handle<Level>> = LEVEL_FILES
+        .iter()
+        .map(|path| asset_server.load(*path))
+        .collect();
+    if save_path(CUSTOM_LEVEL_FILE).exists() {
+        levels.push(asset_server.load(CUSTOM_LEVEL_ASSET));
+    }
+    commands.insert_resource(LevelAssets { levels });
+}
+
+fn spawn_level(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<ColorMaterial>>,
+    mut gravity_well_spawn_events: MessageWriter<GravityWellSpawnMessage>,
+    selected_level: Res<SelectedLevel>,
+    settings: Res<Settings>,
+) {
+    let Some(level) = &selected_level.0 else {
+        return;
+    };
+    for obstacle in level.obstacles.iter() {
+        let (collider, color) = match obstacle.kind {
+            ObstacleKind::Wall { size } => (
+                Collider::rectangle(size.x, size.y),
+                Color::srgb(0.4, 0.4, 0.5),
+            ),
+            ObstacleKind::Station { radius } => {
+                (Collider::circle(radius), Color::srgb(0.3, 0.5, 0.6))
+            }
+            ObstacleKind::Mine => (Collider::circle(MINE_RADIUS), Color::srgb(0.8, 0.2, 0.1)),
+        };
+        let mut entity = commands.spawn((
+            Name::new(format!("Obstacle {:?}", obstacle.kind)),
+            Transform::from_translation(obstacle.position.extend(1.0))
+                .with_rotation(Quat::from_rotation_z(obstacle.rotation)),
+            Obstacle {
+                kind: obstacle.kind,
+            },
+            CollisionLayers::new(
+                GameLayer::Obstacle,
+                [GameLayer::Player, GameLayer::Asteroid, GameLayer::Laser],
+            ),
+            RigidBody::Static,
+            collider,
+            DespawnOnExit(AppState::Game),
+        ));
+        match (settings.render_style, obstacle.kind) {
+            (RenderStyle::Sprite, ObstacleKind::Wall { size }) => {
+                entity.insert(Sprite::from_color(color, size));
+            }
+            (RenderStyle::Sprite, ObstacleKind::Station { radius }) => {
+                entity.insert((
+                    Mesh2d(meshes.add(Circle::new(radius))),
+                    MeshMaterial2d(materials.add(color)),
+                ));
+            }
+            (RenderStyle::Sprite, ObstacleKind::Mine) => {
+                entity.insert((
+                    Mesh2d(meshes.add(RegularPolygon::new(MINE_RADIUS, 8))),
+                    MeshMaterial2d(materials.add(color)),
+                ));
+            }
+            (RenderStyle::Vector, ObstacleKind::Wall { size }) => {
+                entity.insert(VectorShape::rectangle(size));
+            }
+            (RenderStyle::Vector, ObstacleKind::Station { radius }) => {
+                entity.insert(VectorShape::regular_polygon(radius, 24));
+            }
+            (RenderStyle::Vector, ObstacleKind::Mine) => {
+                entity.insert(VectorShape::regular_polygon(MINE_RADIUS, 8));
+            }
+        }
+        // Mines are fragile, and do not push the ship away, they explode on it.
+        if obstacle.kind == ObstacleKind::Mine {
+            entity
+                .insert((Health::new(1), Sensor))
+                .observe(on_mine_damage)
+                .observe(on_mine_destroyed);
+        }
+    }
+    for zone in level.hazard_zones.iter() {
+        commands.spawn((
+            Name::new("HazardZone"),
+            Transform::from_translation(zone.position.extend(0.5)),
+            HazardZone {
+                radius: zone.radius,
+            },
+            DespawnOnExit(AppState::Game),
+        ));
+    }
+    for gravity_well in level.gravity_wells.iter() {
+        gravity_well_spawn_events.write(GravityWellSpawnMessage {
+            definition: gravity_well.clone(),
+            wave: None,
+        });
+    }
+}
+
+fn ship_mine_collision(
+    mut commands: Commands,
+    ship_collisions: Query<(Entity, &CollidingEntities, &Transform), With<Ship>>,
+    mines: Query<(&Obstacle, &Transform), With<Health>>,
+) {
+    for (ship, targets, ship_transform) in ship_collisions.iter() {
+        for target in targets.iter() {
+            if let Ok((obstacle, mine_transform)) = mines.get(*target)
+                && obstacle.kind == ObstacleKind::Mine
+            {
+                commands.trigger(Damage {
+                    entity: ship,
+                    source: Some(*target),
+                    amount: 1,
+                    kind: DamageKind::Collision,
+                    projectile: None,
+                    contact: mine_transform.translation.truncate(),
+                });
+                commands.trigger(Damage {
+                    entity: *target,
+                    source: Some(ship),
+                    amount: 1,
+                    kind: DamageKind::Collision,
+                    projectile: None,
+                    contact: ship_transform.translation.truncate(),
+                });
+            }
+        }
+    }
+}
+
+fn on_mine_damage(
+    damage: On<Damage>,
+    mut commands: Commands,
+    mut mines: Query<(&mut Health, &Transform)>,
+) {
+    let (mut health, transform) = mines
+        .get_mut(damage.entity)
+        .expect("Missing Health and Transform on mine damage trigger");
+    if health.damage(damage.amount) {
+        commands.trigger(Destroyed {
+            entity: damage.entity,
+            source: damage.source,
+            kind: damage.kind,
+            position: transform.translation.truncate(),
+        });
+    }
+}
+
+fn on_mine_destroyed(
+    destroyed: On<Destroyed>,
+    mut commands: Commands,
+    mut explosion_spawn_events: MessageWriter<SpawnExplosionMessage>,
+) {
+    explosion_spawn_events.write(SpawnExplosionMessage {
+        kind: ExplosionKind::MineDetonation,
+        x: destroyed.position.x,
+        y: destroyed.position.y,
+    });
+    commands.entity(destroyed.entity).despawn();
+}
+
+// The ship takes damage on every tick spent in a zone, its invincibility time
+// after a hit being the only respite.
+fn hazard_zone_damage(
+    mut commands: Commands,
+    zones: Query<(Entity, &HazardZone, &Transform)>,
+    ships: Query<(Entity, &Position), With<Ship>>,
+) {
+    for (zone_entity, zone, zone_transform) in zones.iter() {
+        let center = zone_transform.translation.truncate();
+        for (ship, position) in ships.iter() {
+            if position.0.distance(center) < zone.radius {
+                commands.trigger(Damage {
+                    entity: ship,
+                    source: Some(zone_entity),
+                    amount: 1,
+                    kind: DamageKind::Hazard,
+                    projectile: None,
+                    contact: position.0,
+                });
+            }
+        }
+    }
+}
+
+// Zones are drawn as a pulsing red circle, filled by a few concentric rings.
+fn draw_hazard_zone(
+    time: Res<Time<Virtual>>,
+    mut gizmos: Gizmos,
+    zones: Query<(&HazardZone, &Transform)>,
+) {
+    let pulse = 0.5 + 0.5 * (time.elapsed_secs() * 3.0).sin();
+    for (zone, transform) in zones.iter() {
+        let center = transform.translation.truncate();
+        gizmos
+            .circle_2d(center, zone.radius, Color::srgba(1.0, 0.2, 0.1, 0.8))
+            .resolution(64);
+        for i in 1..4 {
+            let radius = zone.radius * i as f32 / 4.0;
+            gizmos
+                .circle_2d(
+                    center,
+                    radius,
+                    Color::srgba(1.0, 0.2, 0.1, 0.15 + 0.15 * pulse),
+                )
+                .resolution(48);
+        }
+    }
+}
@@ -0,0 +1,85 @@
+use std::collections::HashMap;
+use std::fmt::Display;
+
+use serde::{Deserialize, Serialize};
+
+use crate::prelude::*;
+
+// Languages of the user interface, each with a string table in `assets/locales`.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Default, Serialize, Deserialize)]
+pub enum Language {
+    #[default]
+    English,
+    French,
+}
+impl Language {
+    pub const ALL: [Language; 2] = [Language::English, Language::French];
+    // Name of the language, in that language
+    pub fn name(&self) -> &'static str {
+        match self {
+            Language::English => "English",
+            Language::French => "Français",
+        }
+    }
+    // Tables are embedded, the menus being displayed from the first frame
+    fn table(&self) -> &'static str {
+        match self {
+            Language::English => include_str!("../assets/locales/en.locale.ron"),
+            Language::French => include_str!("../assets/locales/fr.locale.ron"),
+        }
+    }
+}
+
+// Strings of the selected language, by key.
+// Missing keys fall back to English, then to the key itself, like the names coming from data files.
+#[derive(Debug, Resource)]
+pub struct Locale {
+    pub language: Language,
+    strings: HashMap<String, String>,
+    fallback: HashMap<String, String>,
+}
+impl Locale {
+    pub fn new(language: Language) -> Self {
+        Locale {
+            language,
+            strings: parse_table(language),
+            fallback: parse_table(Language::English),
+        }
+    }
+    pub fn tr(&self, key: &str) -> String {
+        self.strings
+            .get(key)
+            .or_else(|| self.fallback.get(key))
+            .map_or_else(|| key.to_string(), |string| string.clone())
+    }
+    // Replaces the `{name}` placeholders of the string with the arguments
+    pub fn format(&self, key: &str, args: &[(&str, &dyn Display)]) -> String {
+        let mut string = self.tr(key);
+        for (name, value) in args {
+            string = string.replace(&format!("{{{}}}", name), &value.to_string());
+        }
+        string
+    }
+}
+
+fn parse_table(language: Language) -> HashMap<String, String> {
+    ron::from_str(language.table()).unwrap_or_else(|error| {
+        error!("Invalid string table for {:?}: {}", language, error);
+        HashMap::new()
+    })
+}
+
+pub struct LocalePlugin;
+
+impl Plugin for LocalePlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(Locale::new(Language::default()))
+            .add_systems(Update, apply_language.run_if(resource_changed::<Settings>));
+    }
+}
+
+fn apply_language(settings: Res<Settings>, mut locale: ResMut<Locale>) {
+    if locale.language != settings.language {
+        *locale = Locale::new(settings.language);
+    }
+}
@@ -1,27 +1,81 @@
-#![allow(clippy::too_many_arguments)]
+#![allow(clippy::too_many_arguments, clippy::type_complexity)]
+mod accessibility;
+mod achievement;
 mod arena;
 mod assets;
 mod asteroid;
 mod background;
+mod camera_effects;
+mod campaign;
+mod crystal;
+mod daily;
+mod editor;
 mod explosion;
+mod freighter;
+mod hazard;
+mod highscore;
 mod hud;
+mod hull;
 mod laser;
+mod laser_sound;
+mod level;
+mod locale;
 mod menu;
+mod mode;
+mod mutator;
 mod particle_effects;
 mod player_ship;
+mod progression;
+mod projectile;
+mod save;
+mod score;
+mod settings;
+mod shop;
 mod state;
+mod stats;
+mod tween;
+mod vector_graphics;
+mod wave;
+mod weapon;
 
 mod prelude {
+    pub use crate::accessibility::*;
+    pub use crate::achievement::*;
     pub use crate::arena::*;
     pub use crate::assets::*;
     pub use crate::asteroid::*;
     pub use crate::background::*;
+    pub use crate::camera_effects::*;
+    pub use crate::campaign::*;
+    pub use crate::crystal::*;
+    pub use crate::daily::*;
+    pub use crate::editor::*;
     pub use crate::explosion::*;
+    pub use crate::freighter::*;
+    pub use crate::hazard::*;
+    pub use crate::highscore::*;
     pub use crate::hud::*;
+    pub use crate::hull::*;
     pub use crate::laser::*;
+    pub use crate::laser_sound::*;
+    pub use crate::level::*;
+    pub use crate::locale::*;
     pub use crate::menu::*;
+    pub use crate::mode::*;
+    pub use crate::mutator::*;
     pub use crate::player_ship::*;
+    pub use crate::progression::*;
+    pub use crate::projectile::*;
+    pub use crate::save::*;
+    pub use crate::score::*;
+    pub use crate::settings::*;
+    pub use crate::shop::*;
     pub use crate::state::*;
+    pub use crate::stats::*;
+    pub use crate::tween::*;
+    pub use crate::vector_graphics::*;
+    pub use crate::wave::*;
+    pub use crate::weapon::*;
     pub use avian2d::prelude::*;
     pub use bevy::prelude::*;
     pub use bevy::reflect::TypePath;
@@ -38,6 +92,7 @@ fn main() {
     let mut app = App::new();
 
     app.insert_resource(ClearColor(Color::srgb_u8(0, 0, 0)));
+    register_save_source(&mut app);
     app.add_plugins(DefaultPlugins.set(WindowPlugin {
         primary_window: Some(Window {
             title: "Kataster".to_string(),
@@ -50,7 +105,7 @@ fn main() {
     // Add some plugins to help debugging only when compiled in debug mode
     #[cfg(debug_assertions)]
     // Enable Avian2d debug renders
-    app.add_plugins(PhysicsDebugPlugin::default())
+    app.add_plugins(PhysicsDebugPlugin)
         // Enable connection to external tools, like vscode inspector
         .add_plugins(RemotePlugin::default())
         .add_plugins(RemoteHttpPlugin::default());
@@ -61,8 +116,9 @@ fn main() {
         app.add_plugins(particle_effects::ParticleEffectsPlugin);
     }
 
+    // Gameplay runs in FixedUpdate, physics transforms are interpolated for rendering.
     app.add_plugins((
-        PhysicsPlugins::default(),
+        PhysicsPlugins::default().set(PhysicsInterpolationPlugin::interpolate_all()),
         InputManagerPlugin::<MenuAction>::default(),
     ));
 
@@ -78,6 +134,36 @@ fn main() {
         ExplosionPlugin,
         BackgroundPlugin,
     ));
+    app.add_plugins((
+        SettingsPlugin,
+        VectorGraphicsPlugin,
+        ScorePlugin,
+        WeaponPlugin,
+        LaserSoundPlugin,
+        ProjectilePlugin,
+        HazardPlugin,
+        WavePlugin,
+        LevelPlugin,
+        EditorPlugin,
+        CrystalPlugin,
+        FreighterPlugin,
+        CampaignPlugin,
+        MutatorPlugin,
+        DailyPlugin,
+    ));
+    app.add_plugins((
+        HighScorePlugin,
+        ModePlugin,
+        HullPlugin,
+        ShopPlugin,
+        ProgressionPlugin,
+        AchievementPlugin,
+        StatsPlugin,
+        TweenPlugin,
+        LocalePlugin,
+        AccessibilityPlugin,
+        CameraEffectsPlugin,
+    ));
 
     app.add_systems(OnEnter(AppState::Setup), setup_camera);
 
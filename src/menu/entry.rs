@@ -0,0 +1,252 @@
+use std::sync::Arc;
+
+use crate::prelude::*;
+
+// Callback of the menu entries, run with exclusive access to the world.
+pub type MenuCallback<T> = Arc<dyn Fn(&mut World, T) + Send + Sync>;
+
+// What happens when a button entry is accepted.
+#[derive(Clone)]
+pub enum MenuCommand {
+    // Go to another screen, the current one is pushed on the `MenuStack`
+    Open(AppState),
+    // Go back to the screen on top of the `MenuStack`, or to the main menu
+    Back,
+    // Change the game state, from the in-game menus
+    Game(GameState),
+    Exit,
+    // Screen specific action, like buying an upgrade
+    Run(MenuCallback<()>),
+    // Both commands, in order
+    Then(Box<MenuCommand>, Box<MenuCommand>),
+}
+impl MenuCommand {
+    pub fn run(f: impl Fn(&mut World) + Send + Sync + 'static) -> Self {
+        MenuCommand::Run(Arc::new(move |world, ()| f(world)))
+    }
+    pub fn then(self, next: MenuCommand) -> Self {
+        MenuCommand::Then(Box::new(self), Box::new(next))
+    }
+    // Commands leaving the current menu wait for its exit animation, see `PendingMenuCommand`
+    pub fn leaves_menu(&self) -> bool {
+        match self {
+            MenuCommand::Open(_) | MenuCommand::Back | MenuCommand::Game(_) | MenuCommand::Exit => {
+                true
+            }
+            MenuCommand::Run(_) => false,
+            MenuCommand::Then(first, second) => first.leaves_menu() || second.leaves_menu(),
+        }
+    }
+    pub fn apply(&self, world: &mut World) {
+        match self {
+            MenuCommand::Open(state) => {
+                let current = *world.resource::<State<AppState>>().get();
+                if current != AppState::Game && current != *state {
+                    world.resource_mut::<MenuStack>().0.push(current);
+                }
+                world.resource_mut::<NextState<AppState>>().set(*state);
+            }
+            MenuCommand::Back => {
+                let state = world
+                    .resource_mut::<MenuStack>()
+                    .0
+                    .pop()
+                    .unwrap_or(AppState::Menu);
+                world.resource_mut::<NextState<AppState>>().set(state);
+            }
+            MenuCommand::Game(state) => {
+                world.resource_mut::<NextState<GameState>>().set(*state);
+            }
+            MenuCommand::Exit => {
+                world.write_message(AppExit::Success);
+            }
+            MenuCommand::Run(f) => f(world, ()),
+            MenuCommand::Then(first, second) => {
+                first.apply(world);
+                second.apply(world);
+            }
+        }
+    }
+}
+
+// Command leaving the current menu, applied once its exit animation is over.
+// The menus ignore the inputs in the meantime.
+#[derive(Resource)]
+pub struct PendingMenuCommand {
+    pub command: MenuCommand,
+    pub timer: Timer,
+}
+
+// Screens opened from the menus, in order, to go back to them.
+// Cleared on the main menu and when a game starts.
+#[derive(Debug, Default, Resource)]
+pub struct MenuStack(pub Vec<AppState>);
+
+// How an entry reacts to the menu actions.
+#[derive(Clone)]
+pub enum MenuWidget {
+    // Does nothing, for entries only showing a description
+    Label,
+    Button(MenuCommand),
+    // Switched on accept, or with left and right
+    Toggle {
+        value: bool,
+        on_change: MenuCallback<bool>,
+    },
+    // Stepped with left and right
+    Slider {
+        value: f32,
+        min: f32,
+        max: f32,
+        step: f32,
+        on_change: MenuCallback<f32>,
+    },
+    // Cycled on accept, or with left and right
+    Choice {
+        options: Vec<String>,
+        selected: usize,
+        on_change: MenuCallback<usize>,
+    },
+}
+
+// One entry of a `MenuHandler`, built with the constructor of its widget.
+// Label, choice options and description are `Locale` keys.
+#[derive(Clone)]
+pub struct MenuEntry {
+    pub label: String,
+    pub widget: MenuWidget,
+    // Disabled entries are dimmed, and skipped when moving the selection
+    pub enabled: bool,
+    // Displayed on the right side of the entry button
+    pub price: Option<u32>,
+    // Displayed below the entries while the entry is selected
+    pub description: String,
+}
+impl MenuEntry {
+    fn new(label: impl Into<String>, widget: MenuWidget) -> Self {
+        MenuEntry {
+            label: label.into(),
+            widget,
+            enabled: true,
+            price: None,
+            description: String::new(),
+        }
+    }
+    pub fn label(label: impl Into<String>) -> Self {
+        MenuEntry::new(label, MenuWidget::Label)
+    }
+    pub fn button(label: impl Into<String>, command: MenuCommand) -> Self {
+        MenuEntry::new(label, MenuWidget::Button(command))
+    }
+    pub fn toggle(
+        label: impl Into<String>,
+        value: bool,
+        on_change: impl Fn(&mut World, bool) + Send + Sync + 'static,
+    ) -> Self {
+        MenuEntry::new(
+            label,
+            MenuWidget::Toggle {
+                value,
+                on_change: Arc::new(on_change),
+            },
+        )
+    }
+    pub fn slider(
+        label: impl Into<String>,
+        value: f32,
+        (min, max, step): (f32, f32, f32),
+        on_change: impl Fn(&mut World, f32) + Send + Sync + 'static,
+    ) -> Self {
+        MenuEntry::new(
+            label,
+            MenuWidget::Slider {
+                value,
+                min,
+                max,
+                step,
+                on_change: Arc::new(on_change),
+            },
+        )
+    }
+    pub fn choice(
+        label: impl Into<String>,
+        options: Vec<String>,
+        selected: usize,
+        on_change: impl Fn(&mut World, usize) + Send + Sync + 'static,
+    ) -> Self {
+        MenuEntry::new(
+            label,
+            MenuWidget::Choice {
+                options,
+                selected,
+                on_change: Arc::new(on_change),
+            },
+        )
+    }
+    pub fn enabled(mut self, enabled: bool) -> Self {
+        self.enabled = enabled;
+        self
+    }
+    pub fn with_price(mut self, price: Option<u32>) -> Self {
+        self.price = price;
+        self
+    }
+    pub fn with_description(mut self, description: impl Into<String>) -> Self {
+        self.description = description.into();
+        self
+    }
+    // Text of the entry button, with the current value of its widget
+    pub fn text(&self, locale: &Locale) -> String {
+        let label = locale.tr(&self.label);
+        match &self.widget {
+            MenuWidget::Label | MenuWidget::Button(_) => label,
+            MenuWidget::Toggle { value, .. } => {
+                let value = locale.tr(if *value { "menu-on" } else { "menu-off" });
+                format!("{}: {}", label, value)
+            }
+            MenuWidget::Slider {
+                value, min, max, ..
+            } => format!("{}: {:.0}%", label, (value - min) / (max - min) * 100.0),
+            MenuWidget::Choice {
+                options, selected, ..
+            } => format!("{}: {}", label, locale.tr(&options[*selected])),
+        }
+    }
+    // Applies accept, or a left (-1) / right (+1) step, to the widget.
+    // Returns the command to run, if any.
+    pub fn activate(&mut self, step: i32) -> Option<MenuCommand> {
+        match &mut self.widget {
+            MenuWidget::Label => None,
+            MenuWidget::Button(command) => (step == 0).then(|| command.clone()),
+            MenuWidget::Toggle { value, on_change } => {
+                *value = !*value;
+                let (value, on_change) = (*value, on_change.clone());
+                Some(MenuCommand::run(move |world| on_change(world, value)))
+            }
+            MenuWidget::Slider {
+                value,
+                min,
+                max,
+                step: increment,
+                on_change,
+            } => {
+                if step == 0 {
+                    return None;
+                }
+                *value = (*value + step as f32 * *increment).clamp(*min, *max);
+                let (value, on_change) = (*value, on_change.clone());
+                Some(MenuCommand::run(move |world| on_change(world, value)))
+            }
+            MenuWidget::Choice {
+                options,
+                selected,
+                on_change,
+            } => {
+                let step = if step == 0 { 1 } else { step };
+                *selected = (*selected as i32 + step).rem_euclid(options.len() as i32) as usize;
+                let (selected, on_change) = (*selected, on_change.clone());
+                Some(MenuCommand::run(move |world| on_change(world, selected)))
+            }
+        }
+    }
+}
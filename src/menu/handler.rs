@@ -11,12 +11,31 @@ pub struct DrawBlink {
 #[derive(Component)]
 pub struct ButtonId(i32);
 
+// Highlight of an entry button, from 0.0 to 1.0 when selected, see `menu_highlight_system`
+#[derive(Component, Default)]
+pub struct MenuHighlight(f32);
+
+// Text of an entry button, refreshed when the value of its widget changes
+#[derive(Component)]
+pub struct MenuEntryText(i32);
+
+// Title of the menu, refreshed when the language changes
+#[derive(Component)]
+pub struct MenuTitle;
+
+// Text below the entries, describing the selected one
 #[derive(Component)]
+pub struct MenuDescription;
+
+// A menu screen, its entries run their own commands, see `menu_input_system`.
+// The main text is a `Locale` key, texts and colors are filled by `menu_selection_system`.
+#[derive(Component, Default)]
 pub struct MenuHandler {
     pub main_text: String,
-    pub main_text_color: Color,
+    // Color of the title and of the entries, from the `Palette` of the `Accessibility` options
+    pub main_text_color: MenuTone,
     pub main_text_blink: bool,
-    pub entries: Vec<String>,
+    pub entries: Vec<MenuEntry>,
     pub selected_id: i32,
 }
 impl MenuHandler {
@@ -24,34 +43,88 @@ impl MenuHandler {
     const SELECTED_BG: Color = Color::srgb(0.2, 0.2, 0.2);
     const UNSELECTED_BORDER: Color = Color::srgb(0.2, 0.2, 0.2);
     const UNSELECTED_BG: Color = Color::srgb(0.0, 0.0, 0.0);
+    const PRICE_COLOR: Color = Color::srgb_u8(0xF8, 0xE4, 0x73);
+    // Seconds to fully highlight an entry
+    const HIGHLIGHT_DURATION: f32 = 0.12;
+    // Scale of the highlighted entry
+    const HIGHLIGHT_SCALE: f32 = 1.05;
+    pub fn description(&self) -> &str {
+        self.entries
+            .get(self.selected_id as usize)
+            .map_or("", |entry| entry.description.as_str())
+    }
+    // Entries with a price or a description, like the items of the shop
+    fn has_info(&self) -> bool {
+        self.entries
+            .iter()
+            .any(|entry| entry.price.is_some() || !entry.description.is_empty())
+    }
+    // Moves the selection by `step`, skipping the disabled entries
+    pub fn select_next(&mut self, step: i32) {
+        let len = self.entries.len() as i32;
+        let mut id = self.selected_id;
+        for _ in 0..len {
+            id = (id + step).rem_euclid(len);
+            if self.entries[id as usize].enabled {
+                self.selected_id = id;
+                return;
+            }
+        }
+    }
     pub fn spawn(self, commands: &mut Commands, font: Handle<Font>) -> Entity {
+        // Entries with information are wider, to fit their price
+        let has_info = self.has_info();
+        let (width, padding, justify_content) = if has_info {
+            (400.0, 10.0, JustifyContent::SpaceBetween)
+        } else {
+            (150.0, 0.0, JustifyContent::Center)
+        };
         let buttons = self
             .entries
             .iter()
             .enumerate()
             .map(|(i, entry)| {
+                let price = entry.price.map_or(String::new(), |price| price.to_string());
+                // Disabled entries are dimmed
+                let alpha = if entry.enabled { 1.0 } else { 0.3 };
                 (
                     Button,
                     Node {
-                        width: Val::Px(150.0),
+                        width: Val::Px(width),
                         height: Val::Px(45.0),
                         border: UiRect::all(Val::Px(5.0)),
-                        justify_content: JustifyContent::Center,
+                        padding: UiRect::horizontal(Val::Px(padding)),
+                        justify_content,
                         align_items: AlignItems::Center,
                         margin: UiRect::all(Val::Px(5.0)),
                         ..default()
                     },
                     BorderRadius::all(Val::Px(10.0)),
+                    BorderColor::all(MenuHandler::UNSELECTED_BORDER),
+                    BackgroundColor(MenuHandler::UNSELECTED_BG),
                     ButtonId(i as i32),
-                    children![(
-                        Text::new(entry),
-                        TextFont {
-                            font: font.clone(),
-                            font_size: 25.0,
-                            ..default()
-                        },
-                        TextColor(self.main_text_color),
-                    )],
+                    MenuHighlight::default(),
+                    children![
+                        (
+                            Text::default(),
+                            TextFont {
+                                font: font.clone(),
+                                font_size: 25.0,
+                                ..default()
+                            },
+                            TextColor(Color::WHITE.with_alpha(alpha)),
+                            MenuEntryText(i as i32),
+                        ),
+                        (
+                            Text::new(price),
+                            TextFont {
+                                font: font.clone(),
+                                font_size: 25.0,
+                                ..default()
+                            },
+                            TextColor(MenuHandler::PRICE_COLOR),
+                        )
+                    ],
                 )
             })
             .collect::<Vec<_>>();
@@ -65,22 +138,26 @@ impl MenuHandler {
                     flex_direction: FlexDirection::Column,
                     ..default()
                 },
+                UiTween::new(MENU_TWEEN_DURATION).fade().pop(0.9),
                 children![
                     (
                         Node {
-                            height: Val::Percent(50.0),
+                            // Half of the screen, less when there are many entries
+                            flex_grow: 1.0,
+                            max_height: Val::Percent(50.0),
                             align_items: AlignItems::Center,
                             justify_content: JustifyContent::Center,
                             ..default()
                         },
                         children![(
-                            Text::new(self.main_text.clone()),
+                            Text::default(),
+                            MenuTitle,
                             TextFont {
                                 font: font.clone(),
                                 font_size: 120.0,
                                 ..default()
                             },
-                            TextColor(self.main_text_color),
+                            TextColor(Color::WHITE),
                             DrawBlink {
                                 timer: Timer::from_seconds(0.5, TimerMode::Repeating),
                                 enabled: self.main_text_blink
@@ -100,12 +177,34 @@ impl MenuHandler {
             ))
             .insert(self)
             .id();
+        if has_info {
+            commands.entity(entity).with_child((
+                Text::default(),
+                TextFont {
+                    font,
+                    font_size: 20.0,
+                    ..default()
+                },
+                TextColor(Color::WHITE),
+                TextLayout::new_with_justify(Justify::Center),
+                MenuDescription,
+            ));
+        }
         entity
     }
 }
-pub fn menu_blink_system(time: Res<Time>, mut query: Query<(&mut DrawBlink, &mut Visibility)>) {
+// Without flashing, blinking texts stay visible.
+pub fn menu_blink_system(
+    time: Res<Time>,
+    accessibility: Res<Accessibility>,
+    mut query: Query<(&mut DrawBlink, &mut Visibility)>,
+) {
     for (mut draw_blink, mut visibility) in query.iter_mut() {
-        if draw_blink.enabled {
+        if !accessibility.flashing {
+            if *visibility == Visibility::Hidden {
+                *visibility = Visibility::Inherited;
+            }
+        } else if draw_blink.enabled {
             draw_blink.timer.tick(time.delta());
             if draw_blink.timer.is_finished() {
                 visibility.toggle_inherited_hidden();
@@ -114,28 +213,98 @@ pub fn menu_blink_system(time: Res<Time>, mut query: Query<(&mut DrawBlink, &mut
     }
 }
 
+// Follows the widget values and the description of the selection, see `menu_input_system`.
+// All the texts are translated again when the language changes, and recolored with the palette.
 pub fn menu_selection_system(
-    mut menu: Query<Mut<MenuHandler>>,
-    menu_action_state: Res<ActionState<MenuAction>>,
-    mut buttons: Query<(&ButtonId, &mut BorderColor, &mut BackgroundColor)>,
+    menu: Query<Ref<MenuHandler>>,
+    locale: Res<Locale>,
+    accessibility: Res<Accessibility>,
+    assets: Res<UiAssets>,
+    mut texts: Query<
+        (
+            &mut Text,
+            &mut TextFont,
+            &mut TextColor,
+            Option<&MenuEntryText>,
+            Has<MenuTitle>,
+            Has<MenuDescription>,
+        ),
+        Or<(With<MenuEntryText>, With<MenuTitle>, With<MenuDescription>)>,
+    >,
 ) {
-    if let Ok(mut menu) = menu.single_mut() {
-        if menu_action_state.just_pressed(&MenuAction::MenuUp) {
-            menu.selected_id = (menu.selected_id - 1).rem_euclid(menu.entries.len() as i32);
+    let Ok(menu) = menu.single() else {
+        return;
+    };
+    if !menu.is_changed() && !locale.is_changed() && !accessibility.is_changed() {
+        return;
+    }
+    // Alpha is left to the dimming of disabled entries, and to the `UiTween`
+    let tone = accessibility.palette.color(menu.main_text_color);
+    for (mut text, mut font, mut color, entry_text, is_title, is_description) in texts.iter_mut() {
+        if entry_text.is_some() || is_title {
+            let alpha = color.0.alpha();
+            if color.0 != tone.with_alpha(alpha) {
+                color.0 = tone.with_alpha(alpha);
+            }
         }
-        if menu_action_state.just_pressed(&MenuAction::MenuDown) {
-            menu.selected_id = (menu.selected_id + 1).rem_euclid(menu.entries.len() as i32);
+        let string = if let Some(entry_text) = entry_text {
+            let Some(entry) = menu.entries.get(entry_text.0 as usize) else {
+                continue;
+            };
+            entry.text(&locale)
+        } else if is_title {
+            locale.tr(&menu.main_text)
+        } else if is_description {
+            locale.tr(menu.description())
+        } else {
+            continue;
+        };
+        let handle = assets.font_for(&string);
+        if font.font != handle {
+            font.font = handle;
         }
-        if menu.is_changed() {
-            for (button_id, mut border_color, mut bg_color) in buttons.iter_mut() {
-                if button_id.0 == menu.selected_id {
-                    *border_color = BorderColor::all(MenuHandler::SELECTED_BORDER);
-                    bg_color.0 = MenuHandler::SELECTED_BG;
-                } else {
-                    *border_color = BorderColor::all(MenuHandler::UNSELECTED_BORDER);
-                    bg_color.0 = MenuHandler::UNSELECTED_BG;
-                }
-            }
+        if **text != string {
+            **text = string;
+        }
+    }
+}
+
+// The selected entry button eases into its highlighted colors and scale, the others out of them.
+pub fn menu_highlight_system(
+    time: Res<Time<Real>>,
+    menu: Query<&MenuHandler>,
+    mut buttons: Query<(
+        &ButtonId,
+        &mut MenuHighlight,
+        &mut BorderColor,
+        &mut BackgroundColor,
+        &mut UiTransform,
+    )>,
+) {
+    let Ok(menu) = menu.single() else {
+        return;
+    };
+    let step = time.delta_secs() / MenuHandler::HIGHLIGHT_DURATION;
+    for (button_id, mut highlight, mut border_color, mut bg_color, mut transform) in
+        buttons.iter_mut()
+    {
+        let target = if button_id.0 == menu.selected_id {
+            1.0
+        } else {
+            0.0
+        };
+        if highlight.0 == target {
+            continue;
         }
+        highlight.0 = if highlight.0 < target {
+            (highlight.0 + step).min(target)
+        } else {
+            (highlight.0 - step).max(target)
+        };
+        let h = EaseFunction::QuadraticInOut.sample_clamped(highlight.0);
+        *border_color =
+            BorderColor::all(MenuHandler::UNSELECTED_BORDER.mix(&MenuHandler::SELECTED_BORDER, h));
+        bg_color.0 = MenuHandler::UNSELECTED_BG.mix(&MenuHandler::SELECTED_BG, h);
+        transform.scale = Vec2::splat(1.0 + (MenuHandler::HIGHLIGHT_SCALE - 1.0) * h);
     }
 }
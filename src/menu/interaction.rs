@@ -1,48 +1,86 @@
 use crate::prelude::*;
 
-pub fn main_menu_input_system(
-    app_state: ResMut<State<AppState>>,
-    mut next_app_state: ResMut<NextState<AppState>>,
+// Moves the selection and runs the commands of the entries of the current menu.
+// Back goes to the previous screen, except in game where the menus have their own entries.
+pub fn menu_input_system(
+    mut commands: Commands,
+    app_state: Res<State<AppState>>,
     menu_action_state: Res<ActionState<MenuAction>>,
-    mut app_exit_events: MessageWriter<AppExit>,
-    menu: Query<&MenuHandler>,
+    pending: Option<Res<PendingMenuCommand>>,
+    mut menu: Query<(Entity, &mut MenuHandler)>,
+    children: Query<&Children>,
+    mut tweens: Query<&mut UiTween>,
 ) {
-    if let Ok(menu) = menu.single() {
-        if menu_action_state.just_pressed(&MenuAction::Accept) {
-            if app_state.get() == &AppState::Menu {
-                match menu.selected_id {
-                    0 => {
-                        next_app_state.set(AppState::Game);
-                    }
-                    1 => {
-                        next_app_state.set(AppState::Credits);
-                    }
-                    _ => {
-                        app_exit_events.write(AppExit::Success);
-                    }
-                }
-            }
-            if app_state.get() == &AppState::Credits {
-                match menu.selected_id {
-                    0 => {
-                        next_app_state.set(AppState::Menu);
-                    }
-                    _ => {
-                        app_exit_events.write(AppExit::Success);
-                    }
+    let Ok((entity, mut menu)) = menu.single_mut() else {
+        return;
+    };
+    if pending.is_some() {
+        return;
+    }
+    if menu_action_state.just_pressed(&MenuAction::MenuUp) {
+        menu.select_next(-1);
+    }
+    if menu_action_state.just_pressed(&MenuAction::MenuDown) {
+        menu.select_next(1);
+    }
+    let step = if menu_action_state.just_pressed(&MenuAction::MenuLeft) {
+        Some(-1)
+    } else if menu_action_state.just_pressed(&MenuAction::MenuRight) {
+        Some(1)
+    } else if menu_action_state.just_pressed(&MenuAction::Accept) {
+        Some(0)
+    } else {
+        None
+    };
+    let command = if let Some(step) = step {
+        let selected_id = menu.selected_id as usize;
+        menu.entries
+            .get_mut(selected_id)
+            .filter(|entry| entry.enabled)
+            .and_then(|entry| entry.activate(step))
+    } else if menu_action_state.just_pressed(&MenuAction::Back)
+        && app_state.get() != &AppState::Game
+    {
+        Some(MenuCommand::Back)
+    } else {
+        None
+    };
+    match command {
+        Some(command) if command.leaves_menu() => {
+            for entity in std::iter::once(entity).chain(children.iter_descendants(entity)) {
+                if let Ok(mut tween) = tweens.get_mut(entity) {
+                    tween.play_exit();
                 }
             }
+            commands.insert_resource(PendingMenuCommand {
+                command,
+                timer: Timer::from_seconds(MENU_TWEEN_DURATION, TimerMode::Once),
+            });
+        }
+        Some(command) => {
+            commands.queue(move |world: &mut World| command.apply(world));
         }
+        None => {}
+    }
+}
+
+pub fn pending_menu_command_system(
+    mut commands: Commands,
+    time: Res<Time<Real>>,
+    mut pending: ResMut<PendingMenuCommand>,
+) {
+    pending.timer.tick(time.delta());
+    if pending.timer.is_finished() {
+        commands.remove_resource::<PendingMenuCommand>();
+        let command = pending.command.clone();
+        commands.queue(move |world: &mut World| command.apply(world));
     }
 }
 
 pub fn game_menu_input_system(
     game_state: ResMut<State<GameState>>,
-    mut next_app_state: ResMut<NextState<AppState>>,
     mut next_game_state: ResMut<NextState<GameState>>,
     menu_action_state: Res<ActionState<MenuAction>>,
-    mut app_exit_events: MessageWriter<AppExit>,
-    menu: Query<&MenuHandler>,
 ) {
     if menu_action_state.just_pressed(&MenuAction::PauseUnpause) {
         if game_state.get() == &GameState::Running {
@@ -52,31 +90,4 @@ pub fn game_menu_input_system(
             next_game_state.set(GameState::Running);
         }
     }
-    if let Ok(menu) = menu.single() {
-        if menu_action_state.just_pressed(&MenuAction::Accept) {
-            if game_state.get() == &GameState::Paused {
-                match menu.selected_id {
-                    0 => {
-                        next_game_state.set(GameState::Running);
-                    }
-                    1 => {
-                        next_app_state.set(AppState::Menu);
-                    }
-                    _ => {
-                        app_exit_events.write(AppExit::Success);
-                    }
-                }
-            }
-            if game_state.get() == &GameState::Over {
-                match menu.selected_id {
-                    0 => {
-                        next_app_state.set(AppState::Menu);
-                    }
-                    _ => {
-                        app_exit_events.write(AppExit::Success);
-                    }
-                }
-            }
-        }
-    }
 }
@@ -1,7 +1,9 @@
 use crate::prelude::*;
 
+mod entry;
 mod handler;
 mod interaction;
+pub use entry::*;
 pub use handler::*;
 pub use interaction::*;
 
@@ -12,8 +14,14 @@ pub enum MenuAction {
     MenuUp,
     // In menus move down the highlighted entry
     MenuDown,
+    // In menus, decrease the value of the highlighted slider or choice
+    MenuLeft,
+    // In menus, increase the value of the highlighted slider or choice
+    MenuRight,
     // In menus, select highlighted entry
     Accept,
+    // In menus, go back to the previous screen
+    Back,
     // During gameplay, pause the game.
     // Also directly unpause the game when in the pause screen.
     PauseUnpause,
@@ -22,16 +30,28 @@ pub enum MenuAction {
 pub struct MenuPlugin;
 impl Plugin for MenuPlugin {
     fn build(&self, app: &mut App) {
-        app.add_systems(OnEnter(AppState::Setup), setup)
-            .add_systems(OnEnter(AppState::Menu), spawn_main_menu)
+        app.init_resource::<MenuStack>()
+            .add_systems(OnEnter(AppState::Setup), setup)
+            .add_systems(OnEnter(AppState::Menu), (spawn_main_menu, clear_menu_stack))
+            .add_systems(OnEnter(AppState::Game), clear_menu_stack)
             .add_systems(OnEnter(AppState::Credits), spawn_credits_menu)
+            .add_systems(OnEnter(AppState::Settings), spawn_settings_menu)
+            .add_systems(OnEnter(AppState::LevelSelect), spawn_level_select_menu)
+            .add_systems(OnEnter(AppState::Mutators), spawn_mutators_menu)
             .add_systems(OnEnter(GameState::Paused), spawn_pause_menu)
-            .add_systems(OnEnter(GameState::Over), spawn_gameover_menu)
+            .add_systems(
+                OnEnter(GameState::Over),
+                spawn_gameover_menu
+                    .after(record_high_score)
+                    .after(award_stardust)
+                    .run_if(not(resource_exists::<ActiveMission>)),
+            )
             .add_systems(
                 Update,
                 (
-                    main_menu_input_system,
-                    menu_selection_system,
+                    (menu_input_system, menu_selection_system).chain(),
+                    pending_menu_command_system.run_if(resource_exists::<PendingMenuCommand>),
+                    menu_highlight_system,
                     menu_blink_system,
                 ),
             )
@@ -50,19 +70,40 @@ fn setup(mut commands: Commands) {
         (MenuAction::MenuUp, KeyCode::ArrowUp),
         (MenuAction::MenuDown, KeyCode::KeyS),
         (MenuAction::MenuDown, KeyCode::ArrowDown),
+        (MenuAction::MenuLeft, KeyCode::KeyA),
+        (MenuAction::MenuLeft, KeyCode::ArrowLeft),
+        (MenuAction::MenuRight, KeyCode::KeyD),
+        (MenuAction::MenuRight, KeyCode::ArrowRight),
+        (MenuAction::Back, KeyCode::Backspace),
     ]);
     // Insert MenuAction resources
     commands.insert_resource(input_map);
     commands.insert_resource(ActionState::<MenuAction>::default());
 }
 
+fn clear_menu_stack(mut stack: ResMut<MenuStack>) {
+    stack.0.clear();
+}
+
 fn spawn_main_menu(mut commands: Commands, assets: ResMut<UiAssets>) {
     let entity = MenuHandler {
         main_text: "Kataster".into(),
-        main_text_color: Color::srgb(0.0, 0.7, 0.7),
+        main_text_color: MenuTone::Title,
         main_text_blink: false,
         selected_id: 0,
-        entries: vec!["Play".into(), "Credits".into(), "Exit".into()],
+        entries: vec![
+            MenuEntry::button("menu-play", MenuCommand::Open(AppState::ModeSelect)),
+            MenuEntry::button("menu-campaign", MenuCommand::Open(AppState::Campaign)),
+            MenuEntry::button("menu-daily", MenuCommand::Open(AppState::Daily)),
+            MenuEntry::button("menu-hangar", MenuCommand::Open(AppState::Hangar)),
+            MenuEntry::button("menu-upgrades", MenuCommand::Open(AppState::Upgrades)),
+            MenuEntry::button("menu-trophies", MenuCommand::Open(AppState::Achievements)),
+            MenuEntry::button("menu-stats", MenuCommand::Open(AppState::Statistics)),
+            MenuEntry::button("menu-editor", MenuCommand::Open(AppState::Editor)),
+            MenuEntry::button("menu-settings", MenuCommand::Open(AppState::Settings)),
+            MenuEntry::button("menu-credits", MenuCommand::Open(AppState::Credits)),
+            MenuEntry::button("menu-exit", MenuCommand::Exit),
+        ],
     }
     .spawn(&mut commands, assets.font.clone());
     commands
@@ -70,27 +111,146 @@ fn spawn_main_menu(mut commands: Commands, assets: ResMut<UiAssets>) {
         .insert(DespawnOnExit(AppState::Menu));
 }
 
-fn spawn_gameover_menu(mut commands: Commands, assets: ResMut<UiAssets>) {
+// Summary of the run, between the title and the entries: a banner for a new best score,
+// the result depending on the game mode, then the details of the run.
+// Retry starts a new run with the same level, mode and mutators.
+fn spawn_gameover_menu(
+    mut commands: Commands,
+    assets: ResMut<UiAssets>,
+    locale: Res<Locale>,
+    arena: Res<Arena>,
+    mode: Res<SelectedMode>,
+    clock: Res<ModeClock>,
+    seed: Res<RunSeed>,
+    high_scores: Res<HighScores>,
+    progression: Res<Progression>,
+    run_stats: Res<CurrentRunStats>,
+    mutators: Res<Mutators>,
+    playtest: Option<Res<EditorPlaytest>>,
+) {
+    let (title, result) = clock.results(mode.0, arena.score, &locale);
+    let banner = match high_scores.last_rank {
+        Some(0) => locale.tr("banner-new-high-score"),
+        Some(rank) => locale.format("banner-rank", &[("rank", &(rank + 1))]),
+        None => String::new(),
+    };
+    let mut details = vec![];
+    if mode.0 != GameMode::Classic {
+        details.push(locale.tr(mode.0.key()));
+    }
+    details.push(locale.format("summary-wave", &[("wave", &arena.wave)]));
+    details.extend(run_stats.run.summary(&locale));
+    if !mutators.0.is_empty() {
+        let names = mutators
+            .0
+            .iter()
+            .map(|mutator| locale.tr(mutator.key()))
+            .collect::<Vec<_>>();
+        details.push(locale.format("summary-mutators", &[("mutators", &names.join(", "))]));
+    }
+    if let Some(award) = progression.last_award {
+        details.push(locale.format("summary-stardust", &[("award", &award)]));
+    }
+    let seed = format!("{:016X}", seed.0);
+    details.push(locale.format("summary-seed", &[("seed", &seed)]));
+
     let entity = MenuHandler {
-        main_text: "Game Over".into(),
-        main_text_color: Color::srgb_u8(0xAA, 0x22, 0x22),
+        main_text: title.into(),
+        main_text_color: MenuTone::Alert,
         main_text_blink: false,
         selected_id: 0,
-        entries: vec!["Menu".into(), "Exit".into()],
+        entries: vec![
+            MenuEntry::button("menu-retry", MenuCommand::Game(GameState::Setup)),
+            MenuEntry::button(
+                "menu-menu",
+                MenuCommand::Open(leave_game_state(playtest.is_some())),
+            ),
+            MenuEntry::button("menu-exit", MenuCommand::Exit),
+        ],
     }
     .spawn(&mut commands, assets.font.clone());
+    let summary = commands
+        .spawn((
+            Node {
+                align_items: AlignItems::Center,
+                flex_direction: FlexDirection::Column,
+                ..default()
+            },
+            children![
+                (
+                    Text::new(banner.clone()),
+                    TextFont {
+                        font: assets.font_for(&banner),
+                        font_size: 35.0,
+                        ..default()
+                    },
+                    TextColor(Color::srgb_u8(0xF8, 0xE4, 0x73)),
+                    DrawBlink {
+                        timer: Timer::from_seconds(0.5, TimerMode::Repeating),
+                        enabled: high_scores.last_rank == Some(0),
+                    },
+                ),
+                (
+                    Text::new(result.clone()),
+                    TextFont {
+                        font: assets.font_for(&result),
+                        font_size: 50.0,
+                        ..default()
+                    },
+                    TextColor(Color::srgb_u8(0x00, 0xAA, 0xAA)),
+                ),
+                (
+                    Text::new(details.join("\n")),
+                    TextFont {
+                        font: assets.font_fira.clone(),
+                        font_size: 22.0,
+                        ..default()
+                    },
+                    TextColor(Color::WHITE),
+                    TextLayout::new_with_justify(Justify::Center),
+                )
+            ],
+        ))
+        .id();
+    // The results drop from the top of the screen
     commands
         .entity(entity)
-        .insert(DespawnOnExit(GameState::Over));
+        .insert((
+            DespawnOnExit(GameState::Over),
+            UiTween::new(MENU_TWEEN_DURATION * 2.0)
+                .fade()
+                .slide(Vec2::new(0.0, -200.0)),
+        ))
+        .insert_children(1, &[summary]);
+}
+
+// When test playing a level from the editor, leaving the game goes back to the editor.
+fn leave_game_state(playtest: bool) -> AppState {
+    if playtest {
+        AppState::Editor
+    } else {
+        AppState::Menu
+    }
 }
 
-fn spawn_pause_menu(mut commands: Commands, assets: ResMut<UiAssets>) {
+fn spawn_pause_menu(
+    mut commands: Commands,
+    assets: ResMut<UiAssets>,
+    playtest: Option<Res<EditorPlaytest>>,
+) {
     let entity = MenuHandler {
-        main_text: "Pause".into(),
-        main_text_color: Color::srgb_u8(0xF8, 0xE4, 0x73),
+        main_text: "menu-pause".into(),
+        main_text_color: MenuTone::Accent,
         main_text_blink: true,
         selected_id: 0,
-        entries: vec!["Resume".into(), "Menu".into(), "Exit".into()],
+        entries: vec![
+            MenuEntry::button("menu-resume", MenuCommand::Game(GameState::Running)),
+            MenuEntry::button(
+                "menu-menu",
+                MenuCommand::Open(leave_game_state(playtest.is_some())),
+            ),
+            MenuEntry::button("menu-exit", MenuCommand::Exit),
+        ],
     }
     .spawn(&mut commands, assets.font.clone());
     commands
@@ -98,13 +258,143 @@ fn spawn_pause_menu(mut commands: Commands, assets: ResMut<UiAssets>) {
         .insert(DespawnOnExit(GameState::Paused));
 }
 
-fn spawn_credits_menu(mut commands: Commands, assets: ResMut<UiAssets>) {
+// Each option is a widget displaying its current value, and editing the `Settings` on change.
+fn spawn_settings_menu(mut commands: Commands, assets: ResMut<UiAssets>, settings: Res<Settings>) {
+    let styles = RenderStyle::ALL
+        .map(|style| style.key().to_string())
+        .to_vec();
+    let tick_rates = TICK_RATES
+        .iter()
+        .map(|rate| format!("{}Hz", rate))
+        .collect::<Vec<_>>();
+    let entity = MenuHandler {
+        main_text: "menu-settings".into(),
+        main_text_color: MenuTone::Title,
+        main_text_blink: false,
+        selected_id: 0,
+        entries: vec![
+            MenuEntry::choice(
+                "settings-style",
+                styles,
+                RenderStyle::ALL
+                    .iter()
+                    .position(|style| *style == settings.render_style)
+                    .unwrap_or(0),
+                |world, index| {
+                    world.resource_mut::<Settings>().render_style = RenderStyle::ALL[index]
+                },
+            ),
+            MenuEntry::choice(
+                "settings-tick",
+                tick_rates,
+                TICK_RATES
+                    .iter()
+                    .position(|rate| *rate == settings.tick_rate)
+                    .unwrap_or(0),
+                |world, index| world.resource_mut::<Settings>().tick_rate = TICK_RATES[index],
+            ),
+            MenuEntry::choice(
+                "settings-language",
+                Language::ALL
+                    .map(|language| language.name().to_string())
+                    .to_vec(),
+                Language::ALL
+                    .iter()
+                    .position(|language| *language == settings.language)
+                    .unwrap_or(0),
+                |world, index| world.resource_mut::<Settings>().language = Language::ALL[index],
+            ),
+            MenuEntry::button(
+                "menu-accessibility",
+                MenuCommand::Open(AppState::Accessibility),
+            ),
+            MenuEntry::button("menu-back", MenuCommand::Back),
+        ],
+    }
+    .spawn(&mut commands, assets.font.clone());
+    commands
+        .entity(entity)
+        .insert(DespawnOnExit(AppState::Settings));
+}
+
+// The first entry is the endless arena, without level, then the levels that have been
+// successfully loaded.
+fn spawn_level_select_menu(
+    mut commands: Commands,
+    assets: ResMut<UiAssets>,
+    level_assets: Res<LevelAssets>,
+    levels: Res<Assets<Level>>,
+) {
+    let select_level = |level: Option<Level>| {
+        MenuCommand::run(move |world| world.resource_mut::<SelectedLevel>().0 = level.clone())
+            .then(MenuCommand::Open(AppState::Mutators))
+    };
+    let mut entries = vec![MenuEntry::button("menu-endless", select_level(None))];
+    for handle in level_assets.levels.iter() {
+        if let Some(level) = levels.get(handle) {
+            entries.push(MenuEntry::button(
+                level.name.clone(),
+                select_level(Some(level.clone())),
+            ));
+        }
+    }
+    entries.push(MenuEntry::button("menu-back", MenuCommand::Back));
+    let entity = MenuHandler {
+        main_text: "menu-arena".into(),
+        main_text_color: MenuTone::Title,
+        main_text_blink: false,
+        selected_id: 0,
+        entries,
+    }
+    .spawn(&mut commands, assets.font.clone());
+    commands
+        .entity(entity)
+        .insert(DespawnOnExit(AppState::LevelSelect));
+}
+
+// Mutators can be stacked, each entry toggles one of them.
+fn spawn_mutators_menu(mut commands: Commands, assets: ResMut<UiAssets>, mutators: Res<Mutators>) {
+    let mut entries = vec![MenuEntry::button(
+        "menu-start",
+        MenuCommand::Open(AppState::Game),
+    )];
+    for mutator in Mutator::ALL {
+        entries.push(MenuEntry::toggle(
+            mutator.key(),
+            mutators.has(mutator),
+            move |world, active| {
+                let mut mutators = world.resource_mut::<Mutators>();
+                if mutators.has(mutator) != active {
+                    mutators.toggle(mutator);
+                }
+            },
+        ));
+    }
+    entries.push(MenuEntry::button("menu-back", MenuCommand::Back));
+    let entity = MenuHandler {
+        main_text: "menu-mutators".into(),
+        main_text_color: MenuTone::Title,
+        main_text_blink: false,
+        selected_id: 0,
+        entries,
+    }
+    .spawn(&mut commands, assets.font.clone());
+    commands
+        .entity(entity)
+        .insert(DespawnOnExit(AppState::Mutators));
+}
+
+fn spawn_credits_menu(mut commands: Commands, assets: ResMut<UiAssets>, locale: Res<Locale>) {
+    let (code, assets_title) = (locale.tr("credits-code"), locale.tr("credits-assets"));
     let entity = MenuHandler {
         main_text: "".into(),
-        main_text_color: Color::srgb(0.0, 0.7, 0.7),
+        main_text_color: MenuTone::Title,
         main_text_blink: false,
         selected_id: 0,
-        entries: vec!["Menu".into(), "Exit".into()],
+        entries: vec![
+            MenuEntry::button("menu-menu", MenuCommand::Open(AppState::Menu)),
+            MenuEntry::button("menu-exit", MenuCommand::Exit),
+        ],
     }
     .spawn(&mut commands, assets.font.clone());
     commands
@@ -122,9 +412,9 @@ fn spawn_credits_menu(mut commands: Commands, assets: ResMut<UiAssets>) {
         DespawnOnExit(AppState::Credits),
         children![
             (
-                Text::new("Code"),
+                Text::new(code.clone()),
                 TextFont {
-                    font: assets.font.clone(),
+                    font: assets.font_for(&code),
                     font_size: 50.0,
                     ..default()
                 },
@@ -162,13 +452,13 @@ fn spawn_credits_menu(mut commands: Commands, assets: ResMut<UiAssets>) {
                 ]
             ),
             (
-                Text::new("Assets"),
+                Text::new(assets_title.clone()),
                 Node {
                     margin: UiRect::all(Val::Px(10.)),
                     ..default()
                 },
                 TextFont {
-                    font: assets.font.clone(),
+                    font: assets.font_for(&assets_title),
                     font_size: 50.0,
                     ..default()
                 },
@@ -0,0 +1,197 @@
+use serde::{Deserialize, Serialize};
+
+use crate::prelude::*;
+
+// Duration of a time attack run, in seconds
+pub const TIME_ATTACK_DURATION: f32 = 180.0;
+// Number of big asteroids to destroy in the clear field mode
+pub const CLEAR_FIELD_ASTEROIDS: u32 = 8;
+
+// Rules of a run, deciding what is scored and when the run ends.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash, Default, Serialize, Deserialize)]
+pub enum GameMode {
+    // Score until the ship is destroyed
+    #[default]
+    Classic,
+    // Best score within `TIME_ATTACK_DURATION`
+    TimeAttack,
+    // Last as long as possible, the score is the survived time in seconds
+    Survival,
+    // Destroy a fixed set of asteroids as fast as possible
+    ClearField,
+}
+impl GameMode {
+    pub const ALL: [GameMode; 4] = [
+        GameMode::Classic,
+        GameMode::TimeAttack,
+        GameMode::Survival,
+        GameMode::ClearField,
+    ];
+    // `Locale` key of the name
+    pub fn key(&self) -> &'static str {
+        match self {
+            GameMode::Classic => "mode-classic",
+            GameMode::TimeAttack => "mode-time-attack",
+            GameMode::Survival => "mode-survival",
+            GameMode::ClearField => "mode-clear-field",
+        }
+    }
+    // Destroyed asteroids only give points when the score is not the time.
+    pub fn scores_kills(&self) -> bool {
+        *self != GameMode::Survival
+    }
+    // Clear field runs are ranked on their time, the lowest first, the other modes on their score.
+    pub fn is_better(&self, a: &HighScoreEntry, b: &HighScoreEntry) -> bool {
+        match self {
+            GameMode::ClearField => a.time < b.time,
+            _ => a.score > b.score,
+        }
+    }
+}
+
+// Mode of the next runs, selected before the arena and reset when back to the main menu.
+#[derive(Debug, Default, Resource)]
+pub struct SelectedMode(pub GameMode);
+
+// Time elapsed in the current run, paused with the game.
+#[derive(Debug, Default, Resource)]
+pub struct ModeClock {
+    pub elapsed: f32,
+    // Set when the clear field asteroids have all been destroyed
+    pub cleared: bool,
+}
+impl ModeClock {
+    // Time displayed by the HUD, counting down for time attack
+    pub fn display_time(&self, mode: GameMode) -> Option<f32> {
+        match mode {
+            GameMode::Classic => None,
+            GameMode::TimeAttack => Some((TIME_ATTACK_DURATION - self.elapsed).max(0.0)),
+            GameMode::Survival | GameMode::ClearField => Some(self.elapsed),
+        }
+    }
+    // Title and result line of the results screen, the title being a `Locale` key
+    pub fn results(&self, mode: GameMode, score: u32, locale: &Locale) -> (&'static str, String) {
+        let time = format_time(self.elapsed);
+        match mode {
+            GameMode::TimeAttack if self.elapsed >= TIME_ATTACK_DURATION => (
+                "result-times-up",
+                locale.format("result-score", &[("score", &score)]),
+            ),
+            GameMode::Survival => (
+                "result-game-over",
+                locale.format("result-survived", &[("time", &time)]),
+            ),
+            GameMode::ClearField if self.cleared => (
+                "result-field-cleared",
+                locale.format("result-cleared", &[("time", &time)]),
+            ),
+            _ => (
+                "result-game-over",
+                locale.format("result-score", &[("score", &score)]),
+            ),
+        }
+    }
+}
+
+// Minutes, seconds and tenths, like 2:05.3
+pub fn format_time(secs: f32) -> String {
+    let tenths = (secs * 10.0) as u32;
+    format!("{}:{:02}.{}", tenths / 600, tenths / 10 % 60, tenths % 10)
+}
+
+// The single wave of the clear field mode, replacing the arena waves.
+pub fn clear_field_wave() -> WaveDefinition {
+    WaveDefinition {
+        asteroids: CLEAR_FIELD_ASTEROIDS,
+        spawn_interval: 0.5,
+        gravity_wells: vec![],
+        metal_ratio: 0.0,
+        crystal_chance: 0.0,
+    }
+}
+
+pub struct ModePlugin;
+
+impl Plugin for ModePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<SelectedMode>()
+            .add_systems(OnEnter(AppState::Menu), |mut mode: ResMut<SelectedMode>| {
+                mode.0 = GameMode::Classic;
+            })
+            .add_systems(OnEnter(AppState::ModeSelect), spawn_mode_select_menu)
+            .add_systems(OnEnter(GameState::Setup), |mut commands: Commands| {
+                commands.insert_resource(ModeClock::default());
+            })
+            .add_systems(
+                FixedUpdate,
+                mode_end_system.run_if(in_state(GameState::Running)),
+            );
+    }
+}
+
+fn spawn_mode_select_menu(
+    mut commands: Commands,
+    assets: ResMut<UiAssets>,
+    mode: Res<SelectedMode>,
+) {
+    let mut entries = GameMode::ALL
+        .iter()
+        .map(|selected| {
+            let selected = *selected;
+            MenuEntry::button(
+                selected.key(),
+                MenuCommand::run(move |world| world.resource_mut::<SelectedMode>().0 = selected)
+                    .then(MenuCommand::Open(AppState::LevelSelect)),
+            )
+        })
+        .collect::<Vec<_>>();
+    entries.push(MenuEntry::button(
+        "menu-menu",
+        MenuCommand::Open(AppState::Menu),
+    ));
+    let entity = MenuHandler {
+        main_text: "menu-mode".into(),
+        main_text_color: MenuTone::Title,
+        main_text_blink: false,
+        selected_id: GameMode::ALL.iter().position(|m| *m == mode.0).unwrap_or(0) as i32,
+        entries,
+    }
+    .spawn(&mut commands, assets.font.clone());
+    commands
+        .entity(entity)
+        .insert(DespawnOnExit(AppState::ModeSelect));
+}
+
+// Ship destruction ends a run in every mode, see `on_ship_destroyed`.
+// Time attack also ends when the time is up, and clear field once its only wave is cleared.
+fn mode_end_system(
+    time: Res<Time>,
+    mode: Res<SelectedMode>,
+    mut clock: ResMut<ModeClock>,
+    mut arena: ResMut<Arena>,
+    mut next_game_state: ResMut<NextState<GameState>>,
+    mut wave_cleared_events: MessageReader<WaveClearedMessage>,
+) {
+    clock.elapsed += time.delta_secs();
+    match mode.0 {
+        GameMode::Classic => {}
+        GameMode::TimeAttack => {
+            if clock.elapsed >= TIME_ATTACK_DURATION {
+                next_game_state.set(GameState::Over);
+            }
+        }
+        GameMode::Survival => {
+            let score = clock.elapsed as u32;
+            if arena.score != score {
+                arena.score = score;
+            }
+        }
+        GameMode::ClearField => {
+            if wave_cleared_events.read().any(|event| event.wave == 1) {
+                clock.cleared = true;
+                next_game_state.set(GameState::Over);
+            }
+        }
+    }
+    wave_cleared_events.clear();
+}
@@ -0,0 +1,225 @@
+use bevy::ecs::system::ScheduleSystem;
+use serde::{Deserialize, Serialize};
+
+use crate::prelude::*;
+
+// Gameplay variations stacked on top of a run, selected before it starts
+// or imposed by the daily challenge.
+// Each mutator is implemented by its own plugin below, tweaking entities as they spawn.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash, Serialize, Deserialize)]
+pub enum Mutator {
+    // Asteroids move twice as fast
+    FastAsteroids,
+    // The ship keeps its momentum, no thrust dampening
+    NoDampening,
+    // The ship starts with a single life
+    OneLife,
+    // Every asteroid is made of metal
+    HeavyMetal,
+    // The ship drifts and spins much longer
+    LowGravityDrift,
+    // A single life, but the weapon fires three times faster
+    GlassCannon,
+    // Asteroids are half bigger, colliders included
+    BigHeadAsteroids,
+    // Left and right rotations are swapped
+    MirrorControls,
+}
+impl Mutator {
+    pub const ALL: [Mutator; 8] = [
+        Mutator::FastAsteroids,
+        Mutator::NoDampening,
+        Mutator::OneLife,
+        Mutator::HeavyMetal,
+        Mutator::LowGravityDrift,
+        Mutator::GlassCannon,
+        Mutator::BigHeadAsteroids,
+        Mutator::MirrorControls,
+    ];
+    // `Locale` key of the name
+    pub fn key(&self) -> &'static str {
+        match self {
+            Mutator::FastAsteroids => "mutator-fast-asteroids",
+            Mutator::NoDampening => "mutator-no-dampening",
+            Mutator::OneLife => "mutator-one-life",
+            Mutator::HeavyMetal => "mutator-heavy-metal",
+            Mutator::LowGravityDrift => "mutator-low-gravity",
+            Mutator::GlassCannon => "mutator-glass-cannon",
+            Mutator::BigHeadAsteroids => "mutator-big-heads",
+            Mutator::MirrorControls => "mutator-mirror",
+        }
+    }
+}
+
+// Mutators of the current run, reset when going back to the main menu.
+#[derive(Debug, Default, Resource)]
+pub struct Mutators(pub Vec<Mutator>);
+impl Mutators {
+    pub fn has(&self, mutator: Mutator) -> bool {
+        self.0.contains(&mutator)
+    }
+    pub fn toggle(&mut self, mutator: Mutator) {
+        if let Some(index) = self.0.iter().position(|m| *m == mutator) {
+            self.0.remove(index);
+        } else {
+            self.0.push(mutator);
+        }
+    }
+}
+
+// Run condition of the systems implementing a mutator
+pub fn mutator_active(mutator: Mutator) -> impl Fn(Res<Mutators>) -> bool + Clone {
+    move |mutators: Res<Mutators>| mutators.has(mutator)
+}
+
+pub struct MutatorPlugin;
+
+impl Plugin for MutatorPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Mutators>()
+            .add_systems(OnEnter(AppState::Menu), |mut mutators: ResMut<Mutators>| {
+                mutators.0.clear();
+            })
+            .add_plugins((
+                FastAsteroidsMutator,
+                NoDampeningMutator,
+                OneLifeMutator,
+                HeavyMetalMutator,
+                LowGravityDriftMutator,
+                GlassCannonMutator,
+                BigHeadAsteroidsMutator,
+                MirrorControlsMutator,
+            ));
+    }
+}
+
+// Register the systems of a mutator, running in the gameplay schedule only when it is active.
+fn add_mutator_systems<M>(
+    app: &mut App,
+    mutator: Mutator,
+    systems: impl IntoScheduleConfigs<ScheduleSystem, M>,
+) {
+    app.add_systems(
+        FixedUpdate,
+        systems.run_if(in_state(GameState::Running).and(mutator_active(mutator))),
+    );
+}
+
+struct FastAsteroidsMutator;
+impl Plugin for FastAsteroidsMutator {
+    fn build(&self, app: &mut App) {
+        add_mutator_systems(app, Mutator::FastAsteroids, fast_asteroids);
+    }
+}
+fn fast_asteroids(mut asteroids: Query<&mut LinearVelocity, Added<Asteroid>>) {
+    for mut linvel in asteroids.iter_mut() {
+        linvel.0 *= 2.0;
+    }
+}
+
+struct NoDampeningMutator;
+impl Plugin for NoDampeningMutator {
+    fn build(&self, app: &mut App) {
+        add_mutator_systems(app, Mutator::NoDampening, no_dampening);
+    }
+}
+fn no_dampening(mut ships: Query<&mut Ship, Added<Ship>>) {
+    for mut ship in ships.iter_mut() {
+        ship.linear_dampening = 1.0;
+    }
+}
+
+struct OneLifeMutator;
+impl Plugin for OneLifeMutator {
+    fn build(&self, app: &mut App) {
+        add_mutator_systems(app, Mutator::OneLife, one_life);
+    }
+}
+fn one_life(mut ships: Query<&mut Health, Added<Ship>>) {
+    for mut health in ships.iter_mut() {
+        *health = Health::new(1);
+    }
+}
+
+struct HeavyMetalMutator;
+impl Plugin for HeavyMetalMutator {
+    fn build(&self, app: &mut App) {
+        add_mutator_systems(app, Mutator::HeavyMetal, heavy_metal);
+    }
+}
+fn heavy_metal(
+    mut asteroids: Query<
+        (
+            &mut Asteroid,
+            &mut Health,
+            Option<&mut Sprite>,
+            Option<&mut VectorShape>,
+        ),
+        Added<Asteroid>,
+    >,
+) {
+    for (mut asteroid, mut health, sprite, shape) in asteroids.iter_mut() {
+        if asteroid.material == AsteroidMaterial::Metal {
+            continue;
+        }
+        asteroid.material = AsteroidMaterial::Metal;
+        *health = Health::new(asteroid.size.health() * asteroid.material.factor());
+        if let Some(mut sprite) = sprite {
+            sprite.color = asteroid.material.color();
+        }
+        if let Some(mut shape) = shape {
+            shape.color = asteroid.material.color();
+        }
+    }
+}
+
+struct LowGravityDriftMutator;
+impl Plugin for LowGravityDriftMutator {
+    fn build(&self, app: &mut App) {
+        add_mutator_systems(app, Mutator::LowGravityDrift, low_gravity_drift);
+    }
+}
+fn low_gravity_drift(mut ships: Query<&mut Ship, Added<Ship>>) {
+    for mut ship in ships.iter_mut() {
+        ship.linear_dampening = ship.linear_dampening.max(0.85);
+        ship.angular_dampening = 0.6;
+    }
+}
+
+struct GlassCannonMutator;
+impl Plugin for GlassCannonMutator {
+    fn build(&self, app: &mut App) {
+        add_mutator_systems(app, Mutator::GlassCannon, glass_cannon);
+    }
+}
+fn glass_cannon(mut ships: Query<(&mut Health, &mut Weapon), Added<Ship>>) {
+    for (mut health, mut weapon) in ships.iter_mut() {
+        *health = Health::new(1);
+        weapon.fire_rate *= 3.0;
+    }
+}
+
+struct BigHeadAsteroidsMutator;
+impl Plugin for BigHeadAsteroidsMutator {
+    fn build(&self, app: &mut App) {
+        add_mutator_systems(app, Mutator::BigHeadAsteroids, big_head_asteroids);
+    }
+}
+// Colliders follow the transform scale, as do sprites and vector shapes.
+fn big_head_asteroids(mut asteroids: Query<&mut Transform, Added<Asteroid>>) {
+    for mut transform in asteroids.iter_mut() {
+        transform.scale *= 1.5;
+    }
+}
+
+struct MirrorControlsMutator;
+impl Plugin for MirrorControlsMutator {
+    fn build(&self, app: &mut App) {
+        add_mutator_systems(app, Mutator::MirrorControls, mirror_controls);
+    }
+}
+fn mirror_controls(mut ships: Query<&mut Ship, Added<Ship>>) {
+    for mut ship in ships.iter_mut() {
+        ship.rotation_speed = -ship.rotation_speed;
+    }
+}
@@ -3,6 +3,8 @@ use std::time::Duration;
 use crate::prelude::*;
 
 pub const START_LIFE: u32 = 3;
+// Extra lives can be earned up to this count
+pub const MAX_LIFE: u32 = 5;
 const INVINCIBLE_TIME: f32 = 2.0;
 const MAX_INVINCIBLE_TIME: f32 = 5.0;
 
@@ -15,6 +17,8 @@ pub enum PlayerAction {
     RotateLeft,
     RotateRight,
     Fire,
+    // Cycle through the available weapons
+    NextWeapon,
 }
 
 #[derive(Component)]
@@ -23,16 +27,20 @@ pub struct Ship {
     pub rotation_speed: f32,
     /// Ship thrust N
     pub thrust: f32,
-    /// Ship life points
-    pub life: u32,
-    /// Cannon auto-fire timer
-    pub cannon_timer: Timer,
     /// Id of the controlling player. 1 or 2
     pub player_id: u32,
     // Timer triggered after being hit providing short-term invincibility
     pub invincible_timer: Timer,
     // Total duration of invincibility, accumulating when renewed
     pub invincible_time_secs: f32,
+    // Fraction of the linear and angular velocities kept after one second
+    pub linear_dampening: f32,
+    pub angular_dampening: f32,
+    // Collider radius, from the selected `Hull`
+    pub radius: f32,
+    // Shield charges, each absorbing a hit, recharged up to the capacity bought in the shop
+    pub shield: u32,
+    pub shield_capacity: u32,
 }
 
 pub struct PlayerShipPlugin;
@@ -42,15 +50,18 @@ impl Plugin for PlayerShipPlugin {
         app.add_plugins(InputManagerPlugin::<PlayerAction>::default());
         app.add_systems(OnEnter(GameState::Setup), spawn_ship)
             .add_systems(
-                Update,
+                FixedUpdate,
                 (
                     ship_input_system,
                     ship_dampening_system,
                     ship_timers_system,
-                    ship_invincible_color,
                     ship_asteroid_collision,
                 )
                     .run_if(in_state(GameState::Running)),
+            )
+            .add_systems(
+                Update,
+                ship_invincible_color.run_if(in_state(GameState::Running)),
             );
     }
 }
@@ -59,7 +70,27 @@ impl Plugin for PlayerShipPlugin {
 #[derive(Component)]
 pub struct ExhaustEffect;
 
-fn spawn_ship(mut commands: Commands, handles: Res<SpriteAssets>) {
+// Stats, look and weapon of the ship come from the hull selected in the hangar,
+// improved by the perks of the `Progression`.
+// Daily challenges use the default hull without perks, for every player to get the same run.
+fn spawn_ship(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    settings: Res<Settings>,
+    selected_level: Res<SelectedLevel>,
+    selected_hull: Res<SelectedHull>,
+    progression: Res<Progression>,
+    daily: Option<Res<DailyChallenge>>,
+) {
+    let (default_hull, no_perks) = (Hull::default(), Progression::default());
+    let (hull, progression) = match daily {
+        Some(_) => (&default_hull, &no_perks),
+        None => (&selected_hull.0, &*progression),
+    };
+    let life = hull.life + progression.perk(Perk::ExtraLife);
+    let mut weapon = Weapon::new(hull.weapon);
+    weapon.fire_rate *= 1.1f32.powi(progression.perk(Perk::QuickTrigger) as i32);
+    weapon.available = progression.available_weapons(hull);
     // For player actions, allow keyboard WASD/ Arrows/ Gamepag to control the ship
     let input_map = InputMap::new([
         (PlayerAction::Forward, KeyCode::KeyW),
@@ -69,73 +100,85 @@ fn spawn_ship(mut commands: Commands, handles: Res<SpriteAssets>) {
         (PlayerAction::RotateRight, KeyCode::KeyD),
         (PlayerAction::RotateRight, KeyCode::ArrowRight),
         (PlayerAction::Fire, KeyCode::Space),
+        (PlayerAction::NextWeapon, KeyCode::KeyE),
     ]);
     let mut invincible_timer = Timer::from_seconds(INVINCIBLE_TIME, TimerMode::Once);
     // Straghtaway consume the timer, we don't want invincibility at creation.
     invincible_timer.tick(Duration::from_secs_f32(INVINCIBLE_TIME));
+    let player_start = selected_level
+        .0
+        .as_ref()
+        .map_or(Vec2::ZERO, |level| level.player_start);
 
-    commands
-        .spawn((
-            Name::new("PlayerShip"),
-            Sprite {
-                image: handles.player_ship.clone(),
-                custom_size: Some(Vec2::new(30., 20.)),
-                ..default()
-            },
-            Transform::from_translation(Vec3::new(0.0, 0.0, 1.0)),
-            Ship {
-                rotation_speed: 3.0,
-                thrust: 300000.0,
-                life: START_LIFE,
-                cannon_timer: Timer::from_seconds(0.2, TimerMode::Once),
-                player_id: 1,
-                invincible_timer,
-                invincible_time_secs: 0.0,
-            },
-            DespawnOnExit(AppState::Game),
-            CollisionLayers::new(GameLayer::Player, [GameLayer::Asteroid]),
-            CollidingEntities::default(),
-            RigidBody::Dynamic,
-            Collider::circle(13.5),
-            ConstantForce::default(),
-            LinearVelocity::ZERO,
-            AngularVelocity::ZERO,
-            input_map,
-        ))
-        .observe(on_ship_damage);
+    let mut ship = commands.spawn((
+        Name::new("PlayerShip"),
+        Transform::from_translation(player_start.extend(1.0)),
+        Ship {
+            rotation_speed: hull.rotation_speed,
+            thrust: hull.thrust,
+            player_id: 1,
+            invincible_timer,
+            invincible_time_secs: 0.0,
+            linear_dampening: 0.4,
+            angular_dampening: 0.1,
+            radius: hull.radius,
+            shield: 0,
+            shield_capacity: 0,
+        },
+        Health::new(life.clamp(1, MAX_LIFE)),
+        weapon,
+        DespawnOnExit(AppState::Game),
+        CollisionLayers::new(
+            GameLayer::Player,
+            [GameLayer::Asteroid, GameLayer::Obstacle, GameLayer::Pickup],
+        ),
+        CollidingEntities::default(),
+        RigidBody::Dynamic,
+        Collider::circle(hull.radius),
+        ConstantForce::default(),
+        LinearVelocity::ZERO,
+        AngularVelocity::ZERO,
+        input_map,
+    ));
+    match settings.render_style {
+        RenderStyle::Sprite => ship.insert(Sprite {
+            image: asset_server.load(&hull.sprite),
+            custom_size: Some(hull.sprite_size),
+            ..default()
+        }),
+        RenderStyle::Vector => ship.insert(hull_vector_shape(hull)),
+    };
+    ship.observe(on_ship_damage).observe(on_ship_destroyed);
 }
 
 fn ship_dampening_system(
     time: Res<Time>,
-    mut query: Query<(&mut LinearVelocity, &mut AngularVelocity), With<Ship>>,
+    mut query: Query<(&Ship, &mut LinearVelocity, &mut AngularVelocity)>,
 ) {
-    for (mut linvel, mut angvel) in query.iter_mut() {
+    for (ship, mut linvel, mut angvel) in query.iter_mut() {
         let elapsed = time.delta_secs();
-        angvel.0 *= 0.1f32.powf(elapsed);
-        linvel.0 *= 0.4f32.powf(elapsed);
+        angvel.0 *= ship.angular_dampening.powf(elapsed);
+        linvel.0 *= ship.linear_dampening.powf(elapsed);
     }
 }
 
 fn ship_timers_system(time: Res<Time>, mut ship: Query<&mut Ship>) {
     for mut ship in ship.iter_mut() {
-        ship.cannon_timer.tick(time.delta());
         ship.invincible_timer.tick(time.delta());
     }
 }
 
-#[allow(clippy::type_complexity)]
+// Firing is handled by the equipped `Weapon`, see `weapon_fire_system`
 fn ship_input_system(
-    mut laser_spawn_events: MessageWriter<LaserSpawnMessage>,
     mut query: Query<(
         &ActionState<PlayerAction>,
         &mut ConstantForce,
-        &mut LinearVelocity,
         &mut AngularVelocity,
         &Transform,
-        &mut Ship,
+        &Ship,
     )>,
 ) {
-    for (action_state, mut force, linvel, mut angvel, transform, mut ship) in query.iter_mut() {
+    for (action_state, mut force, mut angvel, transform, ship) in query.iter_mut() {
         let thrust = if action_state.pressed(&PlayerAction::Forward) {
             1.0
         } else {
@@ -148,66 +191,81 @@ fn ship_input_system(
         } else {
             0
         };
-        let fire = action_state.pressed(&PlayerAction::Fire);
         if rotation != 0 {
             angvel.0 = rotation as f32 * ship.rotation_speed;
         }
         force.0 = (transform.rotation * (Vec3::Y * thrust * ship.thrust)).truncate();
-
-        if fire && ship.cannon_timer.is_finished() {
-            laser_spawn_events.write(LaserSpawnMessage {
-                transform: *transform,
-                linvel: *linvel,
-            });
-            ship.cannon_timer.reset();
-        }
     }
 }
 
 fn ship_asteroid_collision(
     mut commands: Commands,
-    ship_collisions: Query<(Entity, &CollidingEntities), With<Ship>>,
-    is_asteroid: Query<(), With<Asteroid>>,
+    ship_collisions: Query<(Entity, &Ship, &CollidingEntities)>,
+    asteroids: Query<&Transform, With<Asteroid>>,
+    transforms: Query<&Transform>,
 ) {
-    for (ship, targets) in ship_collisions.iter() {
+    for (ship, ship_stats, targets) in ship_collisions.iter() {
         for target in targets.iter() {
             // Ship on Asteroid collision
             // The asteroid is unaffected, only the ship takes damage.
             // Possible explosion VFX is handled by the ship damage system.
-            if is_asteroid.contains(*target) {
-                commands.trigger(Damage { entity: ship });
+            // The contact is approximated as the point of the ship hull facing the asteroid.
+            if let Ok(asteroid_transform) = asteroids.get(*target) {
+                let ship_position = transforms
+                    .get(ship)
+                    .expect("Missing transform for the ship")
+                    .translation
+                    .truncate();
+                let direction =
+                    (asteroid_transform.translation.truncate() - ship_position).normalize_or_zero();
+                commands.trigger(Damage {
+                    entity: ship,
+                    source: Some(*target),
+                    amount: 1,
+                    kind: DamageKind::Collision,
+                    projectile: None,
+                    contact: ship_position + direction * ship_stats.radius,
+                });
             }
         }
     }
 }
 
-fn on_ship_damage(
+pub fn on_ship_damage(
     damage: On<Damage>,
     mut commands: Commands,
-    mut next_state: ResMut<NextState<GameState>>,
     mut explosion_spawn_events: MessageWriter<SpawnExplosionMessage>,
-    mut ships: Query<(&mut Ship, &Transform)>,
+    mut camera_effect_events: MessageWriter<CameraEffectMessage>,
+    mut ships: Query<(&mut Ship, &mut Health, &Transform)>,
 ) {
     let ship_entity = damage.entity;
-    let (mut ship, ship_transform) = ships
+    let (mut ship, mut health, ship_transform) = ships
         .get_mut(ship_entity)
-        .expect("Missing Ship and Transform on damage trigger");
+        .expect("Missing Ship, Health and Transform on damage trigger");
     if ship.invincible_timer.is_finished() {
         ship.invincible_time_secs = 0.0;
-        ship.life -= 1;
-        if ship.life == 0 {
+        // The hit is felt with a short freeze and a red border
+        camera_effect_events.write(CameraEffectMessage::HitStop(0.08));
+        camera_effect_events.write(CameraEffectMessage::Vignette(0.6));
+        if ship.shield > 0 {
+            ship.shield -= 1;
             explosion_spawn_events.write(SpawnExplosionMessage {
-                kind: ExplosionKind::ShipDead,
-                x: ship_transform.translation.x,
-                y: ship_transform.translation.y,
+                kind: ExplosionKind::ShipContact,
+                x: damage.contact.x,
+                y: damage.contact.y,
+            });
+        } else if health.damage(damage.amount) {
+            commands.trigger(Destroyed {
+                entity: ship_entity,
+                source: damage.source,
+                kind: damage.kind,
+                position: ship_transform.translation.truncate(),
             });
-            commands.entity(ship_entity).despawn();
-            next_state.set(GameState::Over);
         } else {
             explosion_spawn_events.write(SpawnExplosionMessage {
                 kind: ExplosionKind::ShipContact,
-                x: ship_transform.translation.x,
-                y: ship_transform.translation.y,
+                x: damage.contact.x,
+                y: damage.contact.y,
             });
         }
         ship.invincible_timer.reset();
@@ -220,16 +278,45 @@ fn on_ship_damage(
     }
 }
 
+fn on_ship_destroyed(
+    destroyed: On<Destroyed>,
+    mut commands: Commands,
+    mut next_state: ResMut<NextState<GameState>>,
+    mut explosion_spawn_events: MessageWriter<SpawnExplosionMessage>,
+) {
+    explosion_spawn_events.write(SpawnExplosionMessage {
+        kind: ExplosionKind::ShipDead,
+        x: destroyed.position.x,
+        y: destroyed.position.y,
+    });
+    commands.entity(destroyed.entity).despawn();
+    next_state.set(GameState::Over);
+}
+
 // After contact with an asteroid the ship is invincible for some time.
 // This system make this invincibility visible by dlashing the ship red
-// For 'flashing' we just play with the alpha value of the sprite.
-fn ship_invincible_color(mut ships: Query<(&Ship, &mut Sprite)>) {
-    for (ship, mut ship_sprite) in ships.iter_mut() {
-        if ship.invincible_timer.is_finished() {
-            ship_sprite.color = Color::WHITE;
+// For 'flashing' we just play with the alpha value of the sprite, or of the vector shape.
+// The tint comes from the accessibility palette, and stays steady when flashing is disabled.
+fn ship_invincible_color(
+    accessibility: Res<Accessibility>,
+    mut ships: Query<(&Ship, Option<&mut Sprite>, Option<&mut VectorShape>)>,
+) {
+    for (ship, ship_sprite, ship_shape) in ships.iter_mut() {
+        let color = if ship.invincible_timer.is_finished() {
+            Color::WHITE
         } else {
-            let alpha = (ship.invincible_timer.elapsed_secs() * 2.0) % 1.0;
-            ship_sprite.color = Color::srgba(1.0, 0.4, 0.2, alpha);
+            let alpha = if accessibility.flashing {
+                (ship.invincible_timer.elapsed_secs() * 2.0) % 1.0
+            } else {
+                0.6
+            };
+            accessibility.palette.invincible().with_alpha(alpha)
+        };
+        if let Some(mut ship_sprite) = ship_sprite {
+            ship_sprite.color = color;
+        }
+        if let Some(mut ship_shape) = ship_shape {
+            ship_shape.color = color;
         }
     }
 }
@@ -0,0 +1,321 @@
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::prelude::*;
+
+pub const PROGRESSION_SAVE_FILE: &str = "progression.ron";
+
+// Permanent bonuses bought with stardust, applied to every run.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Serialize, Deserialize)]
+pub enum Perk {
+    // One more life at start
+    ExtraLife,
+    // Faster weapon cooldown from the first shot
+    QuickTrigger,
+}
+impl Perk {
+    pub const ALL: [Perk; 2] = [Perk::ExtraLife, Perk::QuickTrigger];
+    // `Locale` key of the name, the description key adding a `-description` suffix
+    pub fn key(&self) -> &'static str {
+        match self {
+            Perk::ExtraLife => "perk-spare-hull",
+            Perk::QuickTrigger => "perk-quick-trigger",
+        }
+    }
+    pub fn max_level(&self) -> u32 {
+        match self {
+            Perk::ExtraLife => 1,
+            Perk::QuickTrigger => 3,
+        }
+    }
+    pub fn price(&self, level: u32) -> u32 {
+        match self {
+            Perk::ExtraLife => 60,
+            Perk::QuickTrigger => 25 * (level + 1),
+        }
+    }
+}
+
+// Stardust cost to unlock a weapon, 0 for those available from the start.
+pub fn weapon_unlock_price(kind: WeaponKind) -> u32 {
+    match kind {
+        WeaponKind::Single | WeaponKind::Twin => 0,
+        WeaponKind::Spread => 40,
+        WeaponKind::Beam => 60,
+        WeaponKind::Homing => 80,
+    }
+}
+
+// Progress kept across runs, persisted in the save directory.
+// Bump `VERSION` on layout changes, and convert the older files in `migrate`.
+#[derive(Debug, Resource, Serialize, Deserialize)]
+pub struct Progression {
+    // Missing in files written before versioning, read as version 0
+    #[serde(default)]
+    pub version: u32,
+    // Currency earned at the end of each run
+    pub stardust: u32,
+    #[serde(default)]
+    pub perks: BTreeMap<Perk, u32>,
+    // Names of the hulls bought in the upgrades menu
+    #[serde(default)]
+    pub hulls: Vec<String>,
+    #[serde(default)]
+    pub weapons: Vec<WeaponKind>,
+    // Stardust earned by the last finished run
+    #[serde(skip)]
+    pub last_award: Option<u32>,
+}
+impl Default for Progression {
+    fn default() -> Self {
+        Progression {
+            version: Self::VERSION,
+            stardust: 0,
+            perks: BTreeMap::new(),
+            hulls: vec![],
+            weapons: vec![],
+            last_award: None,
+        }
+    }
+}
+impl VersionedSave for Progression {
+    const VERSION: u32 = 1;
+    fn migrate(version: u32, content: &str) -> Result<Self, BevyError> {
+        match version {
+            // Files written before versioning share the first layout
+            0 => Ok(Progression {
+                version: Self::VERSION,
+                ..ron::from_str(content)?
+            }),
+            _ => Err(BevyError::from(std::io::Error::other(format!(
+                "no migration from version {}",
+                version
+            )))),
+        }
+    }
+}
+impl Progression {
+    pub fn perk(&self, perk: Perk) -> u32 {
+        self.perks.get(&perk).copied().unwrap_or(0)
+    }
+    pub fn hull_unlocked(&self, hull: &Hull) -> bool {
+        hull.unlock_price == 0 || self.hulls.contains(&hull.name)
+    }
+    pub fn weapon_unlocked(&self, kind: WeaponKind) -> bool {
+        weapon_unlock_price(kind) == 0 || self.weapons.contains(&kind)
+    }
+    // Weapons a ship can switch to, the weapon of its hull is always available.
+    pub fn available_weapons(&self, hull: &Hull) -> Vec<WeaponKind> {
+        WeaponKind::ALL
+            .into_iter()
+            .filter(|kind| *kind == hull.weapon || self.weapon_unlocked(*kind))
+            .collect()
+    }
+    // Stardust earned by a run, from its score and the waves reached
+    pub fn run_award(score: u32, wave: u32) -> u32 {
+        score / 100 + wave
+    }
+}
+
+// Something that can be bought in the upgrades menu
+#[derive(Debug, Clone)]
+pub enum MetaUpgrade {
+    Perk(Perk),
+    Hull { name: String, price: u32 },
+    Weapon(WeaponKind),
+}
+impl MetaUpgrade {
+    // Price of the next purchase, `None` once fully bought
+    pub fn price(&self, progression: &Progression) -> Option<u32> {
+        match self {
+            MetaUpgrade::Perk(perk) => {
+                let level = progression.perk(*perk);
+                (level < perk.max_level()).then(|| perk.price(level))
+            }
+            MetaUpgrade::Hull { name, price } => {
+                (!progression.hulls.contains(name)).then_some(*price)
+            }
+            MetaUpgrade::Weapon(kind) => {
+                (!progression.weapon_unlocked(*kind)).then(|| weapon_unlock_price(*kind))
+            }
+        }
+    }
+    pub fn label(&self, progression: &Progression, locale: &Locale) -> String {
+        match self {
+            MetaUpgrade::Perk(perk) => format!(
+                "{} {}/{}",
+                locale.tr(perk.key()),
+                progression.perk(*perk),
+                perk.max_level()
+            ),
+            MetaUpgrade::Hull { name, .. } => locale.format("meta-hull", &[("hull", name)]),
+            MetaUpgrade::Weapon(kind) => locale.format("meta-weapon", &[("weapon", kind)]),
+        }
+    }
+    pub fn description(&self, locale: &Locale) -> String {
+        match self {
+            MetaUpgrade::Perk(perk) => locale.tr(&format!("{}-description", perk.key())),
+            MetaUpgrade::Hull { name, .. } => {
+                locale.format("meta-hull-description", &[("hull", name)])
+            }
+            MetaUpgrade::Weapon(kind) => {
+                locale.format("meta-weapon-description", &[("weapon", kind)])
+            }
+        }
+    }
+    fn apply(&self, progression: &mut Progression) {
+        match self {
+            MetaUpgrade::Perk(perk) => {
+                let level = progression.perk(*perk);
+                progression.perks.insert(*perk, level + 1);
+            }
+            MetaUpgrade::Hull { name, .. } => progression.hulls.push(name.clone()),
+            MetaUpgrade::Weapon(kind) => progression.weapons.push(*kind),
+        }
+    }
+    // Spends the stardust, when there is enough
+    fn buy(&self, world: &mut World) {
+        let mut progression = world.resource_mut::<Progression>();
+        if let Some(price) = self.price(&progression)
+            && progression.stardust >= price
+        {
+            progression.stardust -= price;
+            self.apply(&mut progression);
+            write_save(PROGRESSION_SAVE_FILE, &*progression);
+        }
+    }
+}
+
+// Marker of the upgrades menu, respawned on purchase
+#[derive(Component)]
+pub struct UpgradesMenu;
+
+pub struct ProgressionPlugin;
+
+impl Plugin for ProgressionPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(load_versioned_save::<Progression>(PROGRESSION_SAVE_FILE))
+            .add_systems(OnEnter(GameState::Over), award_stardust)
+            .add_systems(OnEnter(AppState::Upgrades), spawn_upgrades_menu)
+            .add_systems(
+                Update,
+                spawn_upgrades_menu
+                    .run_if(in_state(AppState::Upgrades).and(resource_changed::<Progression>)),
+            );
+    }
+}
+
+// Every finished run earns stardust, except editor test plays.
+pub fn award_stardust(
+    mut progression: ResMut<Progression>,
+    arena: Res<Arena>,
+    playtest: Option<Res<EditorPlaytest>>,
+) {
+    if playtest.is_some() {
+        progression.last_award = None;
+        return;
+    }
+    let award = Progression::run_award(arena.score, arena.wave);
+    progression.stardust += award;
+    progression.last_award = Some(award);
+    write_save(PROGRESSION_SAVE_FILE, &*progression);
+}
+
+// Respawned on purchase, keeping the selected entry, without replaying its animation.
+// Only the loaded hulls with an unlock price are listed.
+fn spawn_upgrades_menu(
+    mut commands: Commands,
+    assets: Res<UiAssets>,
+    locale: Res<Locale>,
+    progression: Res<Progression>,
+    hull_assets: Res<HullAssets>,
+    hulls: Res<Assets<Hull>>,
+    menus: Query<(Entity, &MenuHandler), With<UpgradesMenu>>,
+) {
+    let mut selected_id = 0;
+    let respawn = !menus.is_empty();
+    for (entity, menu) in menus.iter() {
+        selected_id = menu.selected_id;
+        commands.entity(entity).despawn();
+    }
+    let mut upgrades = Perk::ALL.map(MetaUpgrade::Perk).to_vec();
+    for hull in hull_assets
+        .hulls
+        .iter()
+        .filter_map(|handle| hulls.get(handle))
+    {
+        if hull.unlock_price > 0 {
+            upgrades.push(MetaUpgrade::Hull {
+                name: hull.name.clone(),
+                price: hull.unlock_price,
+            });
+        }
+    }
+    for kind in WeaponKind::ALL {
+        if weapon_unlock_price(kind) > 0 {
+            upgrades.push(MetaUpgrade::Weapon(kind));
+        }
+    }
+    let mut entries = upgrades
+        .into_iter()
+        .map(|upgrade| {
+            let label = upgrade.label(&progression, &locale);
+            let price = upgrade.price(&progression);
+            let description = upgrade.description(&locale);
+            MenuEntry::button(label, MenuCommand::run(move |world| upgrade.buy(world)))
+                .with_price(price)
+                .with_description(description)
+        })
+        .collect::<Vec<_>>();
+    entries.push(MenuEntry::button(
+        "menu-menu",
+        MenuCommand::Open(AppState::Menu),
+    ));
+    let entity = MenuHandler {
+        main_text: "menu-upgrades".into(),
+        main_text_color: MenuTone::Title,
+        main_text_blink: false,
+        selected_id,
+        entries,
+    }
+    .spawn(&mut commands, assets.font.clone());
+    if respawn {
+        commands.entity(entity).remove::<UiTween>();
+    }
+    commands
+        .entity(entity)
+        .insert((UpgradesMenu, DespawnOnExit(AppState::Upgrades)))
+        .with_child((
+            Text::new(locale.format("meta-stardust", &[("stardust", &progression.stardust)])),
+            TextFont {
+                font: assets.font.clone(),
+                font_size: 30.0,
+                ..default()
+            },
+            TextColor(Color::srgb_u8(0xF8, 0xE4, 0x73)),
+        ));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn migrate_versionless_file() {
+        // Layout written before the `version` field existed
+        let content = r#"(
+            stardust: 130,
+            perks: {ExtraLife: 1, QuickTrigger: 2},
+            hulls: ["Interceptor"],
+            weapons: [Spread],
+        )"#;
+        let progression = Progression::migrate(0, content).unwrap();
+        assert_eq!(progression.version, Progression::VERSION);
+        assert_eq!(progression.stardust, 130);
+        assert_eq!(progression.perk(Perk::ExtraLife), 1);
+        assert_eq!(progression.perk(Perk::QuickTrigger), 2);
+        assert_eq!(progression.hulls, vec!["Interceptor".to_string()]);
+        assert_eq!(progression.weapons, vec![WeaponKind::Spread]);
+    }
+}
@@ -0,0 +1,48 @@
+use crate::prelude::*;
+
+// Common behaviour of fired projectiles, whatever the weapon.
+// A projectile is despawned when either its lifetime elapses or it has travelled its range.
+// Like every other moving body it wraps around the arena edges, see `movement`,
+// the travelled distance being accumulated from its velocity it is not affected by the wrap.
+#[derive(Component)]
+pub struct Projectile {
+    pub lifetime: Timer,
+    // Maximum distance the projectile can travel
+    pub range: f32,
+    // Distance travelled since spawn
+    pub travelled: f32,
+}
+impl Projectile {
+    pub fn new(lifetime: f32, range: f32) -> Self {
+        Projectile {
+            lifetime: Timer::from_seconds(lifetime, TimerMode::Once),
+            range,
+            travelled: 0.0,
+        }
+    }
+}
+
+pub struct ProjectilePlugin;
+
+impl Plugin for ProjectilePlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            FixedUpdate,
+            projectile_expiration_system.run_if(in_state(GameState::Running)),
+        );
+    }
+}
+
+fn projectile_expiration_system(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut query: Query<(Entity, &mut Projectile, &LinearVelocity)>,
+) {
+    for (entity, mut projectile, linvel) in query.iter_mut() {
+        projectile.lifetime.tick(time.delta());
+        projectile.travelled += linvel.length() * time.delta_secs();
+        if projectile.lifetime.is_finished() || projectile.travelled >= projectile.range {
+            commands.entity(entity).despawn();
+        }
+    }
+}
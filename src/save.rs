@@ -0,0 +1,133 @@
+use std::cmp::Ordering;
+use std::path::{Path, PathBuf};
+
+use bevy::asset::io::AssetSourceBuilder;
+use serde::{Deserialize, Serialize, de::DeserializeOwned};
+
+use crate::prelude::*;
+
+// Name of the per-user directory where player data is persisted
+const SAVE_DIR: &str = "kataster";
+
+// Asset source reading player created files, such as custom levels, from the save directory
+pub const SAVE_ASSET_SOURCE: &str = "save";
+
+// The platform data directory of the user, falling back to the working directory when unknown.
+pub fn save_dir() -> PathBuf {
+    let var = |name| std::env::var_os(name).filter(|value| !value.is_empty());
+    let data_dir = if cfg!(target_os = "windows") {
+        var("APPDATA").map(PathBuf::from)
+    } else if cfg!(target_os = "macos") {
+        var("HOME").map(|home| PathBuf::from(home).join("Library/Application Support"))
+    } else {
+        var("XDG_DATA_HOME")
+            .map(PathBuf::from)
+            .or_else(|| var("HOME").map(|home| PathBuf::from(home).join(".local/share")))
+    };
+    data_dir.map_or_else(|| PathBuf::from("save"), |dir| dir.join(SAVE_DIR))
+}
+
+pub fn save_path(file: &str) -> PathBuf {
+    save_dir().join(file)
+}
+
+// Register the asset source of the save directory, must be added before the `AssetPlugin`.
+pub fn register_save_source(app: &mut App) {
+    app.register_asset_source(
+        SAVE_ASSET_SOURCE,
+        AssetSourceBuilder::platform_default(&save_dir().to_string_lossy(), None),
+    );
+}
+
+// Read a persisted value, falling back to its default when missing or unreadable.
+pub fn load_save<T: DeserializeOwned + Default>(file: &str) -> T {
+    let path = save_path(file);
+    let Ok(content) = std::fs::read_to_string(&path) else {
+        return T::default();
+    };
+    ron::from_str(&content).unwrap_or_else(|error| {
+        warn!("Ignoring invalid save file {}: {}", path.display(), error);
+        T::default()
+    })
+}
+
+// Save data whose layout evolves between releases.
+// Files carry a `version` field, files of older versions are migrated on load instead of being reset.
+pub trait VersionedSave: Serialize + DeserializeOwned + Default {
+    const VERSION: u32;
+    // Convert the content of a file written with an older `version` to the current layout
+    fn migrate(version: u32, content: &str) -> Result<Self, BevyError>;
+}
+
+// The part of a versioned file read before its content, files without a version are version 0.
+#[derive(Deserialize)]
+struct SaveHeader {
+    #[serde(default)]
+    version: u32,
+}
+
+// Read a versioned save, migrating it when written by an older version.
+// Files that can not be read are copied aside before falling back to the default,
+// so that the next save does not wipe them.
+pub fn load_versioned_save<T: VersionedSave>(file: &str) -> T {
+    let path = save_path(file);
+    let Ok(content) = std::fs::read_to_string(&path) else {
+        return T::default();
+    };
+    let result = ron::from_str::<SaveHeader>(&content)
+        .map_err(BevyError::from)
+        .and_then(|header| match header.version.cmp(&T::VERSION) {
+            Ordering::Equal => ron::from_str::<T>(&content).map_err(BevyError::from),
+            Ordering::Less => {
+                info!(
+                    "Migrating save file {} from version {} to {}",
+                    path.display(),
+                    header.version,
+                    T::VERSION
+                );
+                let backup = path.with_extension(format!("ron.v{}", header.version));
+                if let Err(error) = std::fs::copy(&path, &backup) {
+                    warn!("Failed to back up {}: {}", path.display(), error);
+                }
+                T::migrate(header.version, &content)
+            }
+            Ordering::Greater => Err(BevyError::from(std::io::Error::other(format!(
+                "written by a newer version {}",
+                header.version
+            )))),
+        });
+    result.unwrap_or_else(|error| {
+        let backup = path.with_extension("ron.bak");
+        warn!(
+            "Ignoring save file {}, kept as {}: {}",
+            path.display(),
+            backup.display(),
+            error
+        );
+        if let Err(error) = std::fs::copy(&path, &backup) {
+            warn!("Failed to back up {}: {}", path.display(), error);
+        }
+        T::default()
+    })
+}
+
+// Persist a value, failures are only logged as the game can go on without it.
+pub fn write_save<T: Serialize>(file: &str, value: &T) {
+    let path = save_path(file);
+    let result = write_file(&path, value);
+    if let Err(error) = result {
+        error!("Failed to write save file {}: {}", path.display(), error);
+    }
+}
+
+// Write a value at the given path, creating its parent directories.
+pub fn write_file<T: Serialize>(path: &Path, value: &T) -> Result<(), BevyError> {
+    path.parent()
+        .map_or(Ok(()), std::fs::create_dir_all)
+        .map_err(BevyError::from)
+        .and_then(|_| {
+            ron::ser::to_string_pretty(value, ron::ser::PrettyConfig::default())
+                .map_err(BevyError::from)
+        })
+        .and_then(|content| std::fs::write(path, content).map_err(BevyError::from))
+}
@@ -0,0 +1,288 @@
+use std::collections::HashMap;
+
+use crate::prelude::*;
+
+// Maximum delay between two kills to keep the combo chain going
+const COMBO_WINDOW: f32 = 1.5;
+// Number of chained kills needed to raise the multiplier by one
+const KILLS_PER_MULTIPLIER: u32 = 3;
+const MAX_MULTIPLIER: u32 = 8;
+const POPUP_DURATION: f32 = 0.8;
+const POPUP_SPEED: f32 = 40.0;
+
+// Score thresholds at which an extra life is awarded.
+// After the last explicit threshold, an extra life is awarded every `extra_life_every` points.
+#[derive(Debug, Resource)]
+pub struct ScoreConfig {
+    pub extra_life_thresholds: Vec<u32>,
+    pub extra_life_every: u32,
+}
+impl Default for ScoreConfig {
+    fn default() -> Self {
+        ScoreConfig {
+            extra_life_thresholds: vec![2000, 5000],
+            extra_life_every: 5000,
+        }
+    }
+}
+impl ScoreConfig {
+    // First extra life threshold strictly above `score`
+    pub fn next_extra_life(&self, score: u32) -> u32 {
+        if let Some(threshold) = self.extra_life_thresholds.iter().find(|t| **t > score) {
+            *threshold
+        } else {
+            let last = self.extra_life_thresholds.last().copied().unwrap_or(0);
+            let every = self.extra_life_every.max(1);
+            last + ((score - last) / every + 1) * every
+        }
+    }
+}
+
+// Score, combo and extra life tracking of one player.
+#[derive(Debug)]
+pub struct PlayerScore {
+    pub score: u32,
+    // Number of kills chained, each within `COMBO_WINDOW` of the previous one
+    pub chain: u32,
+    pub combo_timer: Timer,
+    // Score to reach for the next extra life
+    pub next_extra_life: u32,
+}
+impl PlayerScore {
+    pub fn new(config: &ScoreConfig) -> Self {
+        PlayerScore {
+            score: 0,
+            chain: 0,
+            combo_timer: Timer::from_seconds(COMBO_WINDOW, TimerMode::Once),
+            next_extra_life: config.next_extra_life(0),
+        }
+    }
+    pub fn multiplier(&self) -> u32 {
+        (1 + self.chain / KILLS_PER_MULTIPLIER).min(MAX_MULTIPLIER)
+    }
+}
+
+// Scores of the current game, by `Ship::player_id`.
+// The arena score is the total of the run, shared by all players.
+#[derive(Debug, Default, Resource)]
+pub struct ScoreTracker {
+    pub players: HashMap<u32, PlayerScore>,
+}
+impl ScoreTracker {
+    // Highest multiplier among the players
+    pub fn multiplier(&self) -> u32 {
+        self.players
+            .values()
+            .map(PlayerScore::multiplier)
+            .max()
+            .unwrap_or(1)
+    }
+}
+
+// Floating text displaying the points of a kill, drifting up and fading out.
+#[derive(Component)]
+pub struct ScorePopup {
+    timer: Timer,
+}
+
+pub struct ScorePlugin;
+
+impl Plugin for ScorePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<ScoreConfig>()
+            .add_systems(OnEnter(GameState::Setup), |mut commands: Commands| {
+                commands.insert_resource(ScoreTracker::default());
+            })
+            .add_systems(
+                FixedUpdate,
+                combo_timer_system.run_if(in_state(GameState::Running)),
+            )
+            .add_systems(
+                Update,
+                animate_score_popup.run_if(in_state(GameState::Running)),
+            )
+            .add_observer(score_on_asteroid_destroyed);
+    }
+}
+
+fn combo_timer_system(time: Res<Time>, mut tracker: ResMut<ScoreTracker>) {
+    for player in tracker.players.values_mut() {
+        player.combo_timer.tick(time.delta());
+        if player.combo_timer.just_finished() {
+            player.chain = 0;
+        }
+    }
+}
+
+// Points, combo and extra lives go to the player of the ship that fired the killing laser.
+// Asteroids destroyed without a ship as source do not score, nor do any in survival mode.
+fn score_on_asteroid_destroyed(
+    destroyed: On<Destroyed>,
+    mut commands: Commands,
+    mut arena: ResMut<Arena>,
+    mut tracker: ResMut<ScoreTracker>,
+    config: Res<ScoreConfig>,
+    mode: Res<SelectedMode>,
+    assets: Res<UiAssets>,
+    asteroids: Query<&Asteroid>,
+    mut ships: Query<(&Ship, &mut Health)>,
+) {
+    let Ok(asteroid) = asteroids.get(destroyed.entity) else {
+        return;
+    };
+    if !mode.0.scores_kills() {
+        return;
+    }
+    let Some((ship, mut ship_health)) = destroyed
+        .source
+        .and_then(|source| ships.get_mut(source).ok())
+    else {
+        return;
+    };
+    let player = tracker
+        .players
+        .entry(ship.player_id)
+        .or_insert_with(|| PlayerScore::new(&config));
+    player.chain += 1;
+    player.combo_timer.reset();
+    let multiplier = player.multiplier();
+    let points = asteroid.score() * multiplier;
+    player.score += points;
+    arena.score += points;
+    while player.score >= player.next_extra_life {
+        if ship_health.current < MAX_LIFE {
+            ship_health.current += 1;
+            ship_health.max = ship_health.max.max(ship_health.current);
+        }
+        player.next_extra_life = config.next_extra_life(player.next_extra_life);
+    }
+    let text = if multiplier > 1 {
+        format!("{} x{}", asteroid.score(), multiplier)
+    } else {
+        format!("{}", points)
+    };
+    commands.spawn((
+        Name::new("ScorePopup"),
+        Text2d::new(text),
+        TextFont {
+            font: assets.font.clone(),
+            font_size: 16.0,
+            ..default()
+        },
+        TextColor(Color::srgb_u8(0x00, 0xAA, 0xAA)),
+        Transform::from_translation(destroyed.position.extend(4.0)),
+        ScorePopup {
+            timer: Timer::from_seconds(POPUP_DURATION, TimerMode::Once),
+        },
+        DespawnOnExit(AppState::Game),
+    ));
+}
+
+fn animate_score_popup(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut popups: Query<(Entity, &mut ScorePopup, &mut Transform, &mut TextColor)>,
+) {
+    for (entity, mut popup, mut transform, mut color) in popups.iter_mut() {
+        popup.timer.tick(time.delta());
+        if popup.timer.is_finished() {
+            commands.entity(entity).despawn();
+        } else {
+            transform.translation.y += POPUP_SPEED * time.delta_secs();
+            color.0.set_alpha(1.0 - popup.timer.fraction());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn create_app() -> App {
+        let mut app = App::new();
+        app.add_plugins(MinimalPlugins)
+            .insert_resource(ScoreConfig {
+                extra_life_thresholds: vec![30],
+                extra_life_every: 1000,
+            })
+            .init_resource::<ScoreTracker>()
+            .init_resource::<SelectedMode>()
+            .insert_resource(UiAssets {
+                font: Handle::default(),
+                font_fira: Handle::default(),
+                ship_life: Handle::default(),
+            })
+            .insert_resource(Arena {
+                asteroid_spawn_timer: Timer::from_seconds(5.0, TimerMode::Once),
+                score: 0,
+                wave: 0,
+                asteroids_to_spawn: 0,
+                waves: vec![],
+                asteroid_spawns: vec![],
+                metal_ratio: 0.0,
+                crystal_chance: 0.0,
+            })
+            .add_observer(score_on_asteroid_destroyed);
+        app
+    }
+
+    fn spawn_ship(app: &mut App, player_id: u32) -> Entity {
+        app.world_mut()
+            .spawn((
+                Ship {
+                    rotation_speed: 3.0,
+                    thrust: 300000.0,
+                    player_id,
+                    invincible_timer: Timer::from_seconds(1.0, TimerMode::Once),
+                    invincible_time_secs: 0.0,
+                    linear_dampening: 0.4,
+                    angular_dampening: 0.1,
+                    radius: 13.0,
+                    shield: 0,
+                    shield_capacity: 0,
+                },
+                Health::new(1),
+            ))
+            .id()
+    }
+
+    // A small rock asteroid, worth 10 points, destroyed by a laser of `ship`
+    fn kill(app: &mut App, ship: Entity) {
+        let asteroid = app
+            .world_mut()
+            .spawn(Asteroid {
+                size: AsteroidSize::Small,
+                material: AsteroidMaterial::Rock,
+            })
+            .id();
+        app.world_mut().trigger(Destroyed {
+            entity: asteroid,
+            source: Some(ship),
+            kind: DamageKind::Laser,
+            position: Vec2::ZERO,
+        });
+        app.world_mut().despawn(asteroid);
+    }
+
+    #[test]
+    fn players_are_credited_separately() {
+        let mut app = create_app();
+        let first = spawn_ship(&mut app, 1);
+        let second = spawn_ship(&mut app, 2);
+        for _ in 0..KILLS_PER_MULTIPLIER {
+            kill(&mut app, first);
+        }
+        kill(&mut app, second);
+        let tracker = app.world().resource::<ScoreTracker>();
+        // The third chained kill of the first player is doubled,
+        // the chain of the second player starts from scratch
+        assert_eq!(tracker.players[&1].score, 40);
+        assert_eq!(tracker.players[&1].chain, KILLS_PER_MULTIPLIER);
+        assert_eq!(tracker.players[&2].score, 10);
+        assert_eq!(tracker.players[&2].chain, 1);
+        assert_eq!(app.world().resource::<Arena>().score, 50);
+        // Only the first player reached the extra life threshold
+        assert_eq!(app.world().get::<Health>(first).unwrap().current, 2);
+        assert_eq!(app.world().get::<Health>(second).unwrap().current, 1);
+    }
+}
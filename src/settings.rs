@@ -0,0 +1,57 @@
+use crate::prelude::*;
+
+// How gameplay entities are drawn.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Default)]
+pub enum RenderStyle {
+    // Raster sprites from the Kenney asset pack
+    #[default]
+    Sprite,
+    // Glowing line-art, in the spirit of the 1979 Asteroids arcade cabinet
+    Vector,
+}
+impl RenderStyle {
+    pub const ALL: [RenderStyle; 2] = [RenderStyle::Sprite, RenderStyle::Vector];
+    // `Locale` key of the name
+    pub fn key(&self) -> &'static str {
+        match self {
+            RenderStyle::Sprite => "style-sprite",
+            RenderStyle::Vector => "style-vector",
+        }
+    }
+}
+
+// Gameplay simulation rates, in Hz, that can be selected in the Settings menu.
+pub const TICK_RATES: [f64; 4] = [30.0, 60.0, 64.0, 120.0];
+
+// User selectable options, edited from the Settings menu.
+#[derive(Debug, Resource)]
+pub struct Settings {
+    pub render_style: RenderStyle,
+    // Rate of the FixedUpdate schedule running the whole gameplay simulation.
+    // Rendering is independent, transforms being interpolated between ticks.
+    pub tick_rate: f64,
+    // Language of the user interface, see `Locale`
+    pub language: Language,
+}
+impl Default for Settings {
+    fn default() -> Self {
+        Settings {
+            render_style: RenderStyle::default(),
+            tick_rate: 64.0,
+            language: Language::default(),
+        }
+    }
+}
+
+pub struct SettingsPlugin;
+
+impl Plugin for SettingsPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Settings>()
+            .add_systems(Update, apply_tick_rate.run_if(resource_changed::<Settings>));
+    }
+}
+
+fn apply_tick_rate(settings: Res<Settings>, mut time: ResMut<Time<Fixed>>) {
+    time.set_timestep_hz(settings.tick_rate);
+}
@@ -0,0 +1,314 @@
+use std::collections::HashMap;
+
+use crate::prelude::*;
+
+// Run upgrades sold in the shop, opened between waves.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum Upgrade {
+    Thrust,
+    Rotation,
+    Cooldown,
+    MaxLife,
+    Shield,
+}
+impl Upgrade {
+    pub const ALL: [Upgrade; 5] = [
+        Upgrade::Thrust,
+        Upgrade::Rotation,
+        Upgrade::Cooldown,
+        Upgrade::MaxLife,
+        Upgrade::Shield,
+    ];
+    // `Locale` key of the name, the description key adding a `-description` suffix
+    pub fn key(&self) -> &'static str {
+        match self {
+            Upgrade::Thrust => "upgrade-thrust",
+            Upgrade::Rotation => "upgrade-rotation",
+            Upgrade::Cooldown => "upgrade-cannon",
+            Upgrade::MaxLife => "upgrade-armor",
+            Upgrade::Shield => "upgrade-shield",
+        }
+    }
+    pub fn description(&self) -> String {
+        format!("{}-description", self.key())
+    }
+    pub fn max_level(&self) -> u32 {
+        match self {
+            Upgrade::MaxLife | Upgrade::Shield => 2,
+            _ => 3,
+        }
+    }
+    // Price of the next level, each level is more expensive than the previous
+    pub fn price(&self, level: u32) -> u32 {
+        let base = match self {
+            Upgrade::Thrust | Upgrade::Rotation => 30,
+            Upgrade::Cooldown => 50,
+            Upgrade::MaxLife => 80,
+            Upgrade::Shield => 60,
+        };
+        base * (level + 1)
+    }
+}
+
+// Credits earned in the current run, by destroying asteroids.
+#[derive(Debug, Default, Resource)]
+pub struct Wallet {
+    pub credits: u32,
+}
+
+// Upgrade levels bought in the current run, lost at its end.
+#[derive(Debug, Default, Resource)]
+pub struct RunUpgrades {
+    pub levels: HashMap<Upgrade, u32>,
+}
+impl RunUpgrades {
+    pub fn level(&self, upgrade: Upgrade) -> u32 {
+        self.levels.get(&upgrade).copied().unwrap_or(0)
+    }
+}
+
+// Marker of the shop menu, respawned on purchase
+#[derive(Component)]
+pub struct ShopMenu;
+
+pub struct ShopPlugin;
+
+impl Plugin for ShopPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(OnEnter(GameState::Setup), |mut commands: Commands| {
+            commands.insert_resource(Wallet::default());
+            commands.insert_resource(RunUpgrades::default());
+        })
+        .add_systems(OnEnter(GameState::Shop), spawn_shop_menu)
+        .add_systems(OnExit(GameState::Shop), recharge_shields)
+        .add_systems(
+            Update,
+            spawn_shop_menu.run_if(in_state(GameState::Shop).and(resource_changed::<Wallet>)),
+        )
+        .add_systems(
+            FixedUpdate,
+            open_shop_system.run_if(in_state(GameState::Running)),
+        )
+        .add_observer(credits_on_asteroid_destroyed);
+    }
+}
+
+// Like the score, credits go to asteroids destroyed by a ship.
+fn credits_on_asteroid_destroyed(
+    destroyed: On<Destroyed>,
+    mut wallet: ResMut<Wallet>,
+    asteroids: Query<&Asteroid>,
+    ships: Query<(), With<Ship>>,
+) {
+    let Ok(asteroid) = asteroids.get(destroyed.entity) else {
+        return;
+    };
+    if destroyed
+        .source
+        .is_some_and(|source| ships.contains(source))
+    {
+        wallet.credits += asteroid.score() / 10;
+    }
+}
+
+// The shop opens once a wave is cleared, also in arenas without wave data, except in
+// campaign missions and in the clear field mode, which has a single wave.
+fn open_shop_system(
+    mut next_game_state: ResMut<NextState<GameState>>,
+    mut wave_cleared_events: MessageReader<WaveClearedMessage>,
+    mode: Res<SelectedMode>,
+    mission: Option<Res<ActiveMission>>,
+    ships: Query<(), With<Ship>>,
+) {
+    if wave_cleared_events.read().count() > 0
+        && mode.0 != GameMode::ClearField
+        && mission.is_none()
+        && !ships.is_empty()
+    {
+        next_game_state.set(GameState::Shop);
+    }
+}
+
+// Respawned on purchase, keeping the selected entry, without replaying its animation.
+fn spawn_shop_menu(
+    mut commands: Commands,
+    assets: Res<UiAssets>,
+    locale: Res<Locale>,
+    wallet: Res<Wallet>,
+    upgrades: Res<RunUpgrades>,
+    menus: Query<(Entity, &MenuHandler), With<ShopMenu>>,
+) {
+    let mut selected_id = 0;
+    let respawn = !menus.is_empty();
+    for (entity, menu) in menus.iter() {
+        selected_id = menu.selected_id;
+        commands.entity(entity).despawn();
+    }
+    let mut entries = vec![];
+    for upgrade in Upgrade::ALL {
+        let level = upgrades.level(upgrade);
+        entries.push(
+            MenuEntry::button(
+                format!(
+                    "{} {}/{}",
+                    locale.tr(upgrade.key()),
+                    level,
+                    upgrade.max_level()
+                ),
+                MenuCommand::run(move |world| buy_upgrade(world, upgrade)),
+            )
+            .with_price((level < upgrade.max_level()).then(|| upgrade.price(level)))
+            .with_description(upgrade.description()),
+        );
+    }
+    entries.push(
+        MenuEntry::button("menu-continue", MenuCommand::Game(GameState::Running))
+            .with_description("shop-continue-description"),
+    );
+    let entity = MenuHandler {
+        main_text: "menu-shop".into(),
+        main_text_color: MenuTone::Accent,
+        main_text_blink: false,
+        selected_id,
+        entries,
+    }
+    .spawn(&mut commands, assets.font.clone());
+    if respawn {
+        commands.entity(entity).remove::<UiTween>();
+    }
+    commands
+        .entity(entity)
+        .insert((ShopMenu, DespawnOnExit(GameState::Shop)))
+        .with_child((
+            Text::new(locale.format("shop-credits", &[("credits", &wallet.credits)])),
+            TextFont {
+                font: assets.font.clone(),
+                font_size: 30.0,
+                ..default()
+            },
+            TextColor(Color::srgb_u8(0xF8, 0xE4, 0x73)),
+        ));
+}
+
+// Applied directly to the ship, when there are enough credits.
+fn buy_upgrade(world: &mut World, upgrade: Upgrade) {
+    let level = world.resource::<RunUpgrades>().level(upgrade);
+    let price = upgrade.price(level);
+    if level >= upgrade.max_level() || world.resource::<Wallet>().credits < price {
+        return;
+    }
+    let mut ships = world.query::<(&mut Ship, &mut Health, &mut Weapon)>();
+    let Ok((mut ship, mut health, mut weapon)) = ships.single_mut(world) else {
+        return;
+    };
+    match upgrade {
+        Upgrade::Thrust => ship.thrust *= 1.15,
+        Upgrade::Rotation => ship.rotation_speed *= 1.15,
+        Upgrade::Cooldown => weapon.fire_rate *= 1.2,
+        Upgrade::MaxLife => {
+            if health.current >= MAX_LIFE {
+                return;
+            }
+            health.current += 1;
+            health.max = health.max.max(health.current);
+        }
+        Upgrade::Shield => {
+            ship.shield_capacity += 1;
+            ship.shield = ship.shield_capacity;
+        }
+    }
+    world.resource_mut::<Wallet>().credits -= price;
+    world
+        .resource_mut::<RunUpgrades>()
+        .levels
+        .insert(upgrade, level + 1);
+}
+
+fn recharge_shields(mut ships: Query<&mut Ship>) {
+    for mut ship in ships.iter_mut() {
+        ship.shield = ship.shield_capacity;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use bevy::{state::app::StatesPlugin, time::TimeUpdateStrategy};
+
+    use super::*;
+
+    fn create_app() -> App {
+        let mut app = App::new();
+        app.add_plugins((MinimalPlugins, StatesPlugin, WavePlugin))
+            .init_state::<AppState>()
+            .add_sub_state::<GameState>()
+            .add_message::<AsteroidSpawnMessage>()
+            .add_message::<GravityWellSpawnMessage>()
+            .init_resource::<SelectedMode>()
+            .insert_resource(GameRng::new(0))
+            .insert_resource(TimeUpdateStrategy::ManualDuration(Duration::from_secs_f64(
+                1.0 / 64.0,
+            )))
+            .add_systems(
+                FixedUpdate,
+                open_shop_system.run_if(in_state(GameState::Running)),
+            );
+        // Classic run, without wave data
+        app.insert_resource(Arena {
+            asteroid_spawn_timer: Timer::from_seconds(5.0, TimerMode::Once),
+            score: 0,
+            wave: 0,
+            asteroids_to_spawn: 0,
+            waves: vec![],
+            asteroid_spawns: vec![],
+            metal_ratio: 0.0,
+            crystal_chance: 0.0,
+        });
+        app.world_mut().spawn(Ship {
+            rotation_speed: 3.0,
+            thrust: 300000.0,
+            player_id: 1,
+            invincible_timer: Timer::from_seconds(1.0, TimerMode::Once),
+            invincible_time_secs: 0.0,
+            linear_dampening: 0.4,
+            angular_dampening: 0.1,
+            radius: 13.0,
+            shield: 0,
+            shield_capacity: 0,
+        });
+        app.world_mut()
+            .resource_mut::<NextState<AppState>>()
+            .set(AppState::Game);
+        app.update();
+        app.world_mut()
+            .resource_mut::<NextState<GameState>>()
+            .set(GameState::Running);
+        app
+    }
+
+    fn game_state(app: &App) -> GameState {
+        *app.world().resource::<State<GameState>>().get()
+    }
+
+    #[test]
+    fn shop_opens_between_open_ended_waves() {
+        let mut app = create_app();
+        for _ in 0..8 {
+            app.update();
+        }
+        assert_eq!(game_state(&app), GameState::Running);
+        assert_eq!(app.world().resource::<Arena>().wave, 1);
+        assert_eq!(
+            app.world().resource::<Arena>().asteroids_to_spawn,
+            OPEN_ENDED_WAVE_ASTEROIDS
+        );
+        // All the asteroids of the first wave have been spawned
+        app.world_mut().resource_mut::<Arena>().asteroids_to_spawn = 0;
+        for _ in 0..8 {
+            app.update();
+        }
+        assert_eq!(game_state(&app), GameState::Shop);
+        assert_eq!(app.world().resource::<Arena>().wave, 2);
+    }
+}
@@ -8,6 +8,19 @@ pub enum AppState {
     Menu,
     Game,
     Credits,
+    Settings,
+    LevelSelect,
+    Editor,
+    Campaign,
+    Briefing,
+    Daily,
+    Mutators,
+    ModeSelect,
+    Hangar,
+    Upgrades,
+    Achievements,
+    Statistics,
+    Accessibility,
 }
 #[derive(SubStates, Debug, Copy, Clone, Hash, Eq, PartialEq, Default)]
 #[source(AppState=AppState::Game)]
@@ -16,6 +29,8 @@ pub enum GameState {
     Setup,
     Running,
     Paused,
+    // Between two waves, upgrades can be bought, see `ShopPlugin`
+    Shop,
     Over,
 }
 
@@ -31,10 +46,31 @@ impl Plugin for StatesPlugin {
                 transition_app_setup_to_menu.run_if(in_state(AppState::Setup)),
                 transition_game_setup_to_running.run_if(in_state(GameState::Setup)),
             ),
+        )
+        .add_systems(
+            OnTransition {
+                exited: GameState::Over,
+                entered: GameState::Setup,
+            },
+            despawn_game_entities,
         );
     }
 }
 
+// Retrying from the game over screen stays in `AppState::Game`,
+// the entities of the previous run are despawned as if leaving it.
+// Run resources, like the `Arena`, are replaced on `GameState::Setup`.
+fn despawn_game_entities(
+    mut commands: Commands,
+    entities: Query<(Entity, &DespawnOnExit<AppState>)>,
+) {
+    for (entity, despawn) in entities.iter() {
+        if despawn.0 == AppState::Game {
+            commands.entity(entity).despawn();
+        }
+    }
+}
+
 fn transition_app_setup_to_menu(mut state: ResMut<NextState<AppState>>) {
     state.set(AppState::Menu);
 }
@@ -0,0 +1,241 @@
+use std::collections::{BTreeMap, HashSet};
+
+use serde::{Deserialize, Serialize};
+
+use crate::prelude::*;
+
+pub const STATISTICS_SAVE_FILE: &str = "statistics.ron";
+
+// Statistics of one run, or the sum of all the runs.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RunStats {
+    pub shots_fired: u32,
+    // Lasers hitting an asteroid or a mine, a piercing laser counts once
+    pub hits: u32,
+    pub destroyed_big: u32,
+    pub destroyed_medium: u32,
+    pub destroyed_small: u32,
+    pub distance_flown: f32,
+    // Seconds spent with a ship in the arena, pauses excluded
+    pub time_alive: f32,
+    // Number of ship destructions for each kind of killing blow
+    pub deaths: BTreeMap<DamageKind, u32>,
+}
+impl RunStats {
+    pub fn accuracy(&self) -> f32 {
+        if self.shots_fired == 0 {
+            0.0
+        } else {
+            self.hits as f32 / self.shots_fired as f32
+        }
+    }
+    pub fn destroyed(&self) -> u32 {
+        self.destroyed_big + self.destroyed_medium + self.destroyed_small
+    }
+    pub fn merge(&mut self, other: &RunStats) {
+        self.shots_fired += other.shots_fired;
+        self.hits += other.hits;
+        self.destroyed_big += other.destroyed_big;
+        self.destroyed_medium += other.destroyed_medium;
+        self.destroyed_small += other.destroyed_small;
+        self.distance_flown += other.distance_flown;
+        self.time_alive += other.time_alive;
+        for (kind, count) in other.deaths.iter() {
+            *self.deaths.entry(*kind).or_default() += count;
+        }
+    }
+    // Lines displayed on the game over screen and on the statistics page
+    pub fn summary(&self, locale: &Locale) -> Vec<String> {
+        let deaths = self
+            .deaths
+            .iter()
+            .map(|(kind, count)| format!("{} {}", locale.tr(kind.key()), count))
+            .collect::<Vec<_>>();
+        let deaths = if deaths.is_empty() {
+            "0".to_string()
+        } else {
+            deaths.join(", ")
+        };
+        vec![
+            locale.format(
+                "stats-shots",
+                &[
+                    ("shots", &self.shots_fired),
+                    ("hits", &self.hits),
+                    ("accuracy", &format!("{:.0}", self.accuracy() * 100.0)),
+                ],
+            ),
+            locale.format(
+                "stats-asteroids",
+                &[
+                    ("total", &self.destroyed()),
+                    ("big", &self.destroyed_big),
+                    ("medium", &self.destroyed_medium),
+                    ("small", &self.destroyed_small),
+                ],
+            ),
+            locale.format(
+                "stats-distance",
+                &[
+                    ("distance", &format!("{:.0}", self.distance_flown)),
+                    ("time", &format_time(self.time_alive)),
+                ],
+            ),
+            locale.format("stats-deaths", &[("deaths", &deaths)]),
+        ]
+    }
+}
+
+// Lifetime statistics, persisted in the save directory.
+#[derive(Debug, Default, Resource, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Statistics {
+    pub runs: u32,
+    pub lifetime: RunStats,
+}
+
+// Statistics of the current run, added to the lifetime ones at the game over or when leaving the game.
+#[derive(Debug, Default, Resource)]
+pub struct CurrentRunStats {
+    pub run: RunStats,
+    // Lasers already counted in the hits
+    hit_projectiles: HashSet<Entity>,
+    // Set once added to the lifetime statistics, so that a run is only counted once
+    recorded: bool,
+}
+
+pub struct StatsPlugin;
+
+impl Plugin for StatsPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(load_save::<Statistics>(STATISTICS_SAVE_FILE))
+            .add_systems(OnEnter(GameState::Setup), |mut commands: Commands| {
+                commands.insert_resource(CurrentRunStats::default());
+            })
+            .add_systems(OnEnter(GameState::Over), record_statistics)
+            .add_systems(OnExit(AppState::Game), record_statistics)
+            .add_systems(OnEnter(AppState::Statistics), spawn_statistics_menu)
+            .add_systems(
+                FixedUpdate,
+                (shots_stats_system, flight_stats_system).run_if(in_state(GameState::Running)),
+            )
+            .add_observer(stats_on_damage)
+            .add_observer(stats_on_destroyed);
+    }
+}
+
+fn shots_stats_system(
+    mut stats: ResMut<CurrentRunStats>,
+    mut laser_spawn_events: MessageReader<LaserSpawnMessage>,
+    ships: Query<(), With<Ship>>,
+) {
+    for event in laser_spawn_events.read() {
+        if ships.contains(event.owner) {
+            stats.run.shots_fired += 1;
+        }
+    }
+}
+
+fn flight_stats_system(
+    time: Res<Time>,
+    mut stats: ResMut<CurrentRunStats>,
+    ships: Query<&LinearVelocity, With<Ship>>,
+) {
+    for linvel in ships.iter() {
+        stats.run.distance_flown += linvel.length() * time.delta_secs();
+        stats.run.time_alive += time.delta_secs();
+    }
+}
+
+// Laser hits fired by a ship, on anything but ships.
+// A laser piercing several asteroids is a single hit, accuracy being the ratio of lasers hitting.
+fn stats_on_damage(
+    damage: On<Damage>,
+    mut stats: ResMut<CurrentRunStats>,
+    ships: Query<(), With<Ship>>,
+) {
+    if damage.kind == DamageKind::Laser
+        && damage.source.is_some_and(|source| ships.contains(source))
+        && !ships.contains(damage.entity)
+        && damage
+            .projectile
+            .is_some_and(|projectile| stats.hit_projectiles.insert(projectile))
+    {
+        stats.run.hits += 1;
+    }
+}
+
+// Asteroids destroyed by a ship, and the destruction of the ships themselves
+fn stats_on_destroyed(
+    destroyed: On<Destroyed>,
+    mut stats: ResMut<CurrentRunStats>,
+    asteroids: Query<&Asteroid>,
+    ships: Query<(), With<Ship>>,
+) {
+    if ships.contains(destroyed.entity) {
+        *stats.run.deaths.entry(destroyed.kind).or_default() += 1;
+    } else if let Ok(asteroid) = asteroids.get(destroyed.entity)
+        && destroyed
+            .source
+            .is_some_and(|source| ships.contains(source))
+    {
+        match asteroid.size {
+            AsteroidSize::Big => stats.run.destroyed_big += 1,
+            AsteroidSize::Medium => stats.run.destroyed_medium += 1,
+            AsteroidSize::Small => stats.run.destroyed_small += 1,
+        }
+    }
+}
+
+// Editor test plays are not counted.
+fn record_statistics(
+    mut statistics: ResMut<Statistics>,
+    current: Option<ResMut<CurrentRunStats>>,
+    playtest: Option<Res<EditorPlaytest>>,
+) {
+    let Some(mut current) = current else {
+        return;
+    };
+    if playtest.is_some() || current.recorded {
+        return;
+    }
+    current.recorded = true;
+    statistics.runs += 1;
+    statistics.lifetime.merge(&current.run);
+    write_save(STATISTICS_SAVE_FILE, &*statistics);
+}
+
+fn spawn_statistics_menu(
+    mut commands: Commands,
+    assets: Res<UiAssets>,
+    locale: Res<Locale>,
+    statistics: Res<Statistics>,
+) {
+    let mut lines = vec![locale.format("stats-runs", &[("runs", &statistics.runs)])];
+    lines.extend(statistics.lifetime.summary(&locale));
+    let entity = MenuHandler {
+        main_text: "menu-stats".into(),
+        main_text_color: MenuTone::Title,
+        main_text_blink: false,
+        selected_id: 0,
+        entries: vec![MenuEntry::button(
+            "menu-menu",
+            MenuCommand::Open(AppState::Menu),
+        )],
+    }
+    .spawn(&mut commands, assets.font.clone());
+    commands
+        .entity(entity)
+        .insert(DespawnOnExit(AppState::Statistics))
+        .with_child((
+            Text::new(lines.join("\n")),
+            TextFont {
+                font: assets.font_fira.clone(),
+                font_size: 25.0,
+                ..default()
+            },
+            TextColor(Color::WHITE),
+            TextLayout::new_with_justify(Justify::Center),
+        ));
+}
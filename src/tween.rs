@@ -0,0 +1,97 @@
+use std::collections::HashMap;
+
+use crate::prelude::*;
+
+// Duration of the menu animations, entering and leaving a screen.
+pub const MENU_TWEEN_DURATION: f32 = 0.25;
+
+// Animation of a UI node and its descendants, played when it appears,
+// and backward when it leaves, see `play_exit`.
+// Uses real time, menus are animated while the game is paused.
+#[derive(Component, Clone)]
+pub struct UiTween {
+    // Texts fade in from transparent
+    pub fade: bool,
+    // Offset in pixels at the start
+    pub slide: Vec2,
+    // Scale at the start
+    pub scale: f32,
+    pub ease: EaseFunction,
+    pub timer: Timer,
+    // Played backward
+    pub exit: bool,
+    // Alpha of the faded texts at rest
+    base_alpha: HashMap<Entity, f32>,
+}
+impl UiTween {
+    pub fn new(duration: f32) -> Self {
+        UiTween {
+            fade: false,
+            slide: Vec2::ZERO,
+            scale: 1.0,
+            ease: EaseFunction::CubicOut,
+            timer: Timer::from_seconds(duration, TimerMode::Once),
+            exit: false,
+            base_alpha: HashMap::new(),
+        }
+    }
+    pub fn fade(mut self) -> Self {
+        self.fade = true;
+        self
+    }
+    pub fn slide(mut self, offset: Vec2) -> Self {
+        self.slide = offset;
+        self
+    }
+    pub fn pop(mut self, scale: f32) -> Self {
+        self.scale = scale;
+        self
+    }
+    pub fn play_exit(&mut self) {
+        self.exit = true;
+        self.timer.reset();
+    }
+    // From 0.0, at the start of the entry animation, to 1.0 when at rest
+    pub fn progress(&self) -> f32 {
+        let t = self.ease.sample_clamped(self.timer.fraction());
+        if self.exit { 1.0 - t } else { t }
+    }
+}
+
+pub struct TweenPlugin;
+
+impl Plugin for TweenPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Update, ui_tween_system);
+    }
+}
+
+// Once finished, a tween leaves its node untouched until played again.
+fn ui_tween_system(
+    time: Res<Time<Real>>,
+    mut tweens: Query<(Entity, &mut UiTween, &mut UiTransform)>,
+    children: Query<&Children>,
+    mut texts: Query<&mut TextColor>,
+) {
+    for (entity, mut tween, mut transform) in tweens.iter_mut() {
+        if tween.timer.is_finished() && !tween.timer.just_finished() {
+            continue;
+        }
+        tween.timer.tick(time.delta());
+        let progress = tween.progress();
+        let offset = tween.slide * (1.0 - progress);
+        transform.translation = Val2::px(offset.x, offset.y);
+        transform.scale = Vec2::splat(tween.scale + (1.0 - tween.scale) * progress);
+        if tween.fade {
+            for text_entity in std::iter::once(entity).chain(children.iter_descendants(entity)) {
+                if let Ok(mut color) = texts.get_mut(text_entity) {
+                    let base = *tween
+                        .base_alpha
+                        .entry(text_entity)
+                        .or_insert(color.0.alpha());
+                    color.0.set_alpha(base * progress);
+                }
+            }
+        }
+    }
+}
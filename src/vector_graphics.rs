@@ -0,0 +1,150 @@
+use bevy::post_process::bloom::Bloom;
+
+use crate::prelude::*;
+
+// Multiplier applied to the line colors in vector mode.
+// Values above 1.0 are what the HDR camera bloom pass turns into a glow.
+const GLOW_INTENSITY: f32 = 4.0;
+
+/// Line-art drawn in place of a `Sprite` when the `RenderStyle::Vector` style is selected.
+/// `points` are in the entity local space, and are drawn with gizmos every frame.
+#[derive(Component, Clone)]
+#[require(Visibility)]
+pub struct VectorShape {
+    pub points: Vec<Vec2>,
+    pub closed: bool,
+    pub color: Color,
+}
+impl VectorShape {
+    // The classic arrow-head ship, sized like the 30x20 sprite.
+    pub fn ship() -> Self {
+        VectorShape {
+            points: vec![
+                Vec2::new(0.0, 13.0),
+                Vec2::new(-9.0, -10.0),
+                Vec2::new(-5.0, -6.0),
+                Vec2::new(5.0, -6.0),
+                Vec2::new(9.0, -10.0),
+            ],
+            closed: true,
+            color: Color::WHITE,
+        }
+    }
+    // A jagged rock outline, each asteroid gets its own random silhouette.
+    pub fn asteroid(radius: f32) -> Self {
+        let mut rng = thread_rng();
+        let n_vertices = 11;
+        let points = (0..n_vertices)
+            .map(|i| {
+                let angle = i as f32 * std::f32::consts::TAU / n_vertices as f32;
+                Vec2::from_angle(angle) * radius * rng.gen_range(0.75..1.1)
+            })
+            .collect();
+        VectorShape {
+            points,
+            closed: true,
+            color: Color::WHITE,
+        }
+    }
+    // Outline of a `size` rectangle centered on the entity, for walls
+    pub fn rectangle(size: Vec2) -> Self {
+        let half = size / 2.0;
+        VectorShape {
+            points: vec![
+                Vec2::new(-half.x, -half.y),
+                Vec2::new(half.x, -half.y),
+                Vec2::new(half.x, half.y),
+                Vec2::new(-half.x, half.y),
+            ],
+            closed: true,
+            color: Color::WHITE,
+        }
+    }
+    // Polygon with `sides` vertices on a circle, high side counts are used for round shapes.
+    pub fn regular_polygon(radius: f32, sides: usize) -> Self {
+        VectorShape {
+            points: (0..sides)
+                .map(|i| Vec2::from_angle(i as f32 * std::f32::consts::TAU / sides as f32) * radius)
+                .collect(),
+            closed: true,
+            color: Color::WHITE,
+        }
+    }
+    // A simple dash along the laser direction
+    pub fn laser() -> Self {
+        VectorShape {
+            points: vec![Vec2::new(0.0, -5.0), Vec2::new(0.0, 5.0)],
+            closed: false,
+            color: Color::WHITE,
+        }
+    }
+}
+
+// Plugin responsible for drawing `VectorShape` entities and for configuring the camera
+// post-processing associated to the selected `RenderStyle`.
+pub struct VectorGraphicsPlugin;
+
+impl Plugin for VectorGraphicsPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Update, apply_camera_render_style)
+            .add_systems(
+                PostUpdate,
+                draw_vector_shapes.after(TransformSystems::Propagate),
+            );
+    }
+}
+
+// The vector style needs a bloom pass for the glowing phosphor look.
+// Applied when the settings change, or when a new camera is spawned.
+fn apply_camera_render_style(
+    mut commands: Commands,
+    settings: Res<Settings>,
+    cameras: Query<(Entity, Ref<Camera2d>)>,
+) {
+    for (entity, camera) in cameras.iter() {
+        if settings.is_changed() || camera.is_added() {
+            match settings.render_style {
+                RenderStyle::Sprite => {
+                    commands.entity(entity).remove::<Bloom>();
+                }
+                RenderStyle::Vector => {
+                    commands.entity(entity).insert(Bloom::OLD_SCHOOL);
+                }
+            }
+        }
+    }
+}
+
+fn draw_vector_shapes(
+    mut gizmos: Gizmos,
+    shapes: Query<(&GlobalTransform, &VectorShape, &InheritedVisibility)>,
+) {
+    for (transform, shape, visibility) in shapes.iter() {
+        if !visibility.get() || shape.points.is_empty() {
+            continue;
+        }
+        let color = shape.color.to_linear();
+        let glow = LinearRgba::new(
+            color.red * GLOW_INTENSITY,
+            color.green * GLOW_INTENSITY,
+            color.blue * GLOW_INTENSITY,
+            color.alpha,
+        );
+        let points = shape
+            .points
+            .iter()
+            .map(|point| transform.transform_point(point.extend(0.0)).truncate());
+        if shape.closed {
+            gizmos.linestrip_2d(
+                points.chain(std::iter::once(
+                    transform
+                        .transform_point(shape.points[0].extend(0.0))
+                        .truncate(),
+                )),
+                glow,
+            );
+        } else {
+            gizmos.linestrip_2d(points, glow);
+        }
+    }
+}
@@ -0,0 +1,127 @@
+use serde::{Deserialize, Serialize};
+
+use crate::prelude::*;
+
+// Content of a wave: the asteroids to spawn and the hazards present in the arena.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WaveDefinition {
+    // Number of big asteroids spawned during the wave
+    pub asteroids: u32,
+    // Delay in seconds before each asteroid spawn
+    pub spawn_interval: f32,
+    #[serde(default)]
+    pub gravity_wells: Vec<GravityWellDefinition>,
+    // Fraction of the spawned asteroids made of metal
+    #[serde(default)]
+    pub metal_ratio: f32,
+    // Chance for an asteroid destroyed by the player to drop a crystal
+    #[serde(default)]
+    pub crystal_chance: f32,
+}
+impl WaveDefinition {
+    // Procedural waves used after the scripted ones, each a bit harder than the previous.
+    // From the third wave, every other wave adds a gravity well at a random position,
+    // and metal asteroids appear from the fourth one.
+    pub fn endless(wave: u32, rng: &mut impl Rng) -> Self {
+        let mut gravity_wells = vec![];
+        if wave >= 3 && wave % 2 == 1 {
+            let side = if rng.gen_bool(0.5) { 1.0 } else { -1.0 };
+            let x = side * rng.gen_range(0.15..0.35) * ARENA_WIDTH;
+            let y = rng.gen_range(-0.3..0.3) * ARENA_HEIGHT;
+            gravity_wells.push(GravityWellDefinition {
+                position: Vec2::new(x, y),
+                strength: 2_000_000.0,
+                core_radius: 20.0,
+            });
+        }
+        WaveDefinition {
+            asteroids: 3 + wave,
+            spawn_interval: (3.0 * 0.85f32.powi(wave as i32 - 1)).max(0.5),
+            gravity_wells,
+            metal_ratio: ((wave as f32 - 3.0) * 0.1).clamp(0.0, 0.5),
+            crystal_chance: 0.0,
+        }
+    }
+}
+
+// Without wave data, a wave ends once this many asteroids have been spawned,
+// the spawn timer keeping its acceleration from one wave to the next.
+pub const OPEN_ENDED_WAVE_ASTEROIDS: u32 = 10;
+
+#[derive(Message)]
+pub struct WaveStartedMessage {
+    pub wave: u32,
+}
+#[derive(Message)]
+pub struct WaveClearedMessage {
+    pub wave: u32,
+}
+
+pub struct WavePlugin;
+
+impl Plugin for WavePlugin {
+    fn build(&self, app: &mut App) {
+        app.add_message::<WaveStartedMessage>()
+            .add_message::<WaveClearedMessage>()
+            .add_systems(
+                FixedUpdate,
+                wave_progress_system.run_if(in_state(GameState::Running)),
+            );
+    }
+}
+
+// A wave is cleared once all its asteroids have been spawned and destroyed,
+// then the next wave starts, replacing the hazards of the previous wave.
+// Without wave data, a wave is cleared as soon as all its asteroids have been spawned.
+fn wave_progress_system(
+    mut commands: Commands,
+    mut arena: ResMut<Arena>,
+    mut rng: ResMut<GameRng>,
+    mut wave_started_events: MessageWriter<WaveStartedMessage>,
+    mut wave_cleared_events: MessageWriter<WaveClearedMessage>,
+    mut gravity_well_spawn_events: MessageWriter<GravityWellSpawnMessage>,
+    mut pending_asteroids: MessageReader<AsteroidSpawnMessage>,
+    asteroids: Query<(), With<Asteroid>>,
+    gravity_wells: Query<(Entity, &GravityWell)>,
+) {
+    // Split asteroids may still be pending as messages, not yet spawned.
+    let pending = !pending_asteroids.is_empty();
+    pending_asteroids.clear();
+    let cleared = arena.asteroids_to_spawn == 0
+        && (arena.is_open_ended() || (asteroids.is_empty() && !pending));
+    if arena.wave > 0 && !cleared {
+        return;
+    }
+    if arena.wave > 0 {
+        wave_cleared_events.write(WaveClearedMessage { wave: arena.wave });
+    }
+    arena.wave += 1;
+    if arena.is_open_ended() {
+        arena.asteroids_to_spawn = OPEN_ENDED_WAVE_ASTEROIDS;
+        wave_started_events.write(WaveStartedMessage { wave: arena.wave });
+        return;
+    }
+    let definition = arena.wave_definition(arena.wave, &mut rng.waves);
+    arena.asteroids_to_spawn = definition.asteroids;
+    arena.metal_ratio = definition.metal_ratio;
+    arena.crystal_chance = definition.crystal_chance;
+    arena
+        .asteroid_spawn_timer
+        .set_duration(std::time::Duration::from_secs_f32(
+            definition.spawn_interval,
+        ));
+    arena.asteroid_spawn_timer.reset();
+    // Level gravity wells are permanent, only those of the previous waves are removed.
+    for (entity, gravity_well) in gravity_wells.iter() {
+        if gravity_well.wave.is_some() {
+            commands.entity(entity).despawn();
+        }
+    }
+    for gravity_well in definition.gravity_wells {
+        gravity_well_spawn_events.write(GravityWellSpawnMessage {
+            definition: gravity_well,
+            wave: Some(arena.wave),
+        });
+    }
+    wave_started_events.write(WaveStartedMessage { wave: arena.wave });
+}
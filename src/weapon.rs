@@ -0,0 +1,316 @@
+use serde::{Deserialize, Serialize};
+
+use crate::prelude::*;
+
+// Distance from the ship center at which projectiles are spawned, just ahead of the ship hull.
+const MUZZLE_OFFSET: f32 = 20.0;
+
+// The different weapons that can be equipped on a ship.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash, Serialize, Deserialize)]
+pub enum WeaponKind {
+    Single,
+    Twin,
+    Spread,
+    Beam,
+    Homing,
+}
+impl std::fmt::Display for WeaponKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:?}", self)
+    }
+}
+
+// A barrel is the origin of one projectile in a salvo,
+// relative to the ship center and heading.
+#[derive(Debug, Copy, Clone)]
+pub struct Barrel {
+    // Lateral offset from the ship center, positive to the right
+    pub offset: f32,
+    // Angle in radians from the ship heading, positive counter-clockwise
+    pub angle: f32,
+}
+
+// Static characteristics of a weapon.
+#[derive(Debug, Clone)]
+pub struct WeaponDefinition {
+    // Minimum delay between two salvos, in seconds
+    pub cooldown: f32,
+    // Projectile speed relative to the ship, in units/s
+    pub speed: f32,
+    // Projectile lifetime, in seconds
+    pub lifetime: f32,
+    // Maximum distance travelled by a projectile
+    pub range: f32,
+    // One projectile is fired from each barrel per salvo
+    pub barrels: &'static [Barrel],
+    // Number of asteroids a projectile passes through before being consumed
+    pub pierce: u32,
+    // Maximum turn rate in rad/s of projectiles seeking the nearest asteroid, 0.0 when not homing
+    pub homing_turn_rate: f32,
+    // Sprite image and display size
+    pub sprite: &'static str,
+    pub size: Vec2,
+    // Projectile color in the vector render style
+    pub color: Color,
+    // Synthesized trigger sound, weapons without one use the Kenney laser sound
+    pub sound: Option<LaserSound>,
+}
+
+impl WeaponKind {
+    pub const ALL: [WeaponKind; 5] = [
+        WeaponKind::Single,
+        WeaponKind::Twin,
+        WeaponKind::Spread,
+        WeaponKind::Beam,
+        WeaponKind::Homing,
+    ];
+    pub fn definition(&self) -> WeaponDefinition {
+        match self {
+            WeaponKind::Single => WeaponDefinition {
+                cooldown: 0.2,
+                speed: 500.0,
+                lifetime: 2.0,
+                range: 700.0,
+                barrels: &[Barrel {
+                    offset: 0.0,
+                    angle: 0.0,
+                }],
+                pierce: 0,
+                homing_turn_rate: 0.0,
+                sprite: "laserRed07.png",
+                size: Vec2::new(5.0, 20.0),
+                color: Color::WHITE,
+                sound: None,
+            },
+            WeaponKind::Twin => WeaponDefinition {
+                cooldown: 0.25,
+                speed: 500.0,
+                lifetime: 2.0,
+                range: 700.0,
+                barrels: &[
+                    Barrel {
+                        offset: -7.0,
+                        angle: 0.0,
+                    },
+                    Barrel {
+                        offset: 7.0,
+                        angle: 0.0,
+                    },
+                ],
+                pierce: 0,
+                homing_turn_rate: 0.0,
+                sprite: "laserGreen_twin.png",
+                size: Vec2::new(4.5, 16.5),
+                color: Color::srgb(0.3, 0.9, 0.35),
+                sound: Some(LaserSound {
+                    waveform: Waveform::Square,
+                    start_frequency: 1400.0,
+                    end_frequency: 500.0,
+                    duration: 0.12,
+                }),
+            },
+            WeaponKind::Spread => WeaponDefinition {
+                cooldown: 0.45,
+                speed: 450.0,
+                lifetime: 1.2,
+                range: 350.0,
+                barrels: &[
+                    Barrel {
+                        offset: 0.0,
+                        angle: -0.3,
+                    },
+                    Barrel {
+                        offset: 0.0,
+                        angle: -0.15,
+                    },
+                    Barrel {
+                        offset: 0.0,
+                        angle: 0.0,
+                    },
+                    Barrel {
+                        offset: 0.0,
+                        angle: 0.15,
+                    },
+                    Barrel {
+                        offset: 0.0,
+                        angle: 0.3,
+                    },
+                ],
+                pierce: 0,
+                homing_turn_rate: 0.0,
+                sprite: "laserOrange_spread.png",
+                size: Vec2::new(6.5, 6.5),
+                color: Color::srgb(1.0, 0.6, 0.15),
+                sound: Some(LaserSound {
+                    waveform: Waveform::Noise,
+                    start_frequency: 900.0,
+                    end_frequency: 150.0,
+                    duration: 0.2,
+                }),
+            },
+            WeaponKind::Beam => WeaponDefinition {
+                cooldown: 0.6,
+                speed: 900.0,
+                lifetime: 1.5,
+                range: 1200.0,
+                barrels: &[Barrel {
+                    offset: 0.0,
+                    angle: 0.0,
+                }],
+                pierce: 3,
+                homing_turn_rate: 0.0,
+                sprite: "laserBlue_beam.png",
+                size: Vec2::new(3.5, 37.5),
+                color: Color::srgb(0.3, 0.55, 1.0),
+                sound: Some(LaserSound {
+                    waveform: Waveform::Saw,
+                    start_frequency: 220.0,
+                    end_frequency: 110.0,
+                    duration: 0.35,
+                }),
+            },
+            WeaponKind::Homing => WeaponDefinition {
+                cooldown: 0.7,
+                speed: 300.0,
+                lifetime: 3.0,
+                range: 900.0,
+                barrels: &[Barrel {
+                    offset: 0.0,
+                    angle: 0.0,
+                }],
+                pierce: 0,
+                homing_turn_rate: 4.0,
+                sprite: "laserPurple_homing.png",
+                size: Vec2::new(7.5, 10.5),
+                color: Color::srgb(0.75, 0.35, 1.0),
+                sound: Some(LaserSound {
+                    waveform: Waveform::Sine,
+                    start_frequency: 400.0,
+                    end_frequency: 1200.0,
+                    duration: 0.25,
+                }),
+            },
+        }
+    }
+
+    // Weapon cycled to with the `NextWeapon` action
+    pub fn next(&self) -> WeaponKind {
+        match self {
+            WeaponKind::Single => WeaponKind::Twin,
+            WeaponKind::Twin => WeaponKind::Spread,
+            WeaponKind::Spread => WeaponKind::Beam,
+            WeaponKind::Beam => WeaponKind::Homing,
+            WeaponKind::Homing => WeaponKind::Single,
+        }
+    }
+}
+
+// The weapon currently equipped on a ship.
+#[derive(Component, Debug)]
+pub struct Weapon {
+    pub kind: WeaponKind,
+    // Rearmed after each salvo with the weapon cooldown
+    pub cooldown_timer: Timer,
+    // Multiplier of the cooldown recovery speed, kept when switching weapons
+    pub fire_rate: f32,
+    // Weapons reachable with `NextWeapon`, kept when switching weapons
+    pub available: Vec<WeaponKind>,
+}
+impl Weapon {
+    pub fn new(kind: WeaponKind) -> Self {
+        let mut cooldown_timer = Timer::from_seconds(kind.definition().cooldown, TimerMode::Once);
+        // A freshly equipped weapon is ready to fire
+        cooldown_timer.tick(cooldown_timer.duration());
+        Weapon {
+            kind,
+            cooldown_timer,
+            fire_rate: 1.0,
+            available: WeaponKind::ALL.to_vec(),
+        }
+    }
+    pub fn equip(&mut self, kind: WeaponKind) {
+        *self = Weapon {
+            fire_rate: self.fire_rate,
+            available: std::mem::take(&mut self.available),
+            ..Weapon::new(kind)
+        };
+    }
+    // Next available weapon in the `WeaponKind::next` cycle
+    pub fn next_available(&self) -> WeaponKind {
+        let mut next = self.kind.next();
+        while next != self.kind && !self.available.contains(&next) {
+            next = next.next();
+        }
+        next
+    }
+}
+
+pub struct WeaponPlugin;
+
+impl Plugin for WeaponPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            FixedUpdate,
+            (weapon_switch_system, weapon_fire_system)
+                .chain()
+                .run_if(in_state(GameState::Running)),
+        );
+    }
+}
+
+fn weapon_switch_system(mut query: Query<(&ActionState<PlayerAction>, &mut Weapon)>) {
+    for (action_state, mut weapon) in query.iter_mut() {
+        if action_state.just_pressed(&PlayerAction::NextWeapon) {
+            let next = weapon.next_available();
+            weapon.equip(next);
+        }
+    }
+}
+
+// Dispatch the `Fire` action to the equipped weapon.
+// Each salvo sends one `LaserSpawnMessage` per weapon barrel and plays the trigger sound once.
+// Projectiles are spawned at the muzzle, outside of the ship collider.
+fn weapon_fire_system(
+    mut commands: Commands,
+    time: Res<Time>,
+    audios: Res<AudioAssets>,
+    mut laser_spawn_events: MessageWriter<LaserSpawnMessage>,
+    mut query: Query<(
+        Entity,
+        &ActionState<PlayerAction>,
+        &Transform,
+        &LinearVelocity,
+        &mut Weapon,
+    )>,
+) {
+    for (entity, action_state, transform, linvel, mut weapon) in query.iter_mut() {
+        let delta = time.delta().mul_f32(weapon.fire_rate);
+        weapon.cooldown_timer.tick(delta);
+        if action_state.pressed(&PlayerAction::Fire) && weapon.cooldown_timer.is_finished() {
+            let definition = weapon.kind.definition();
+            for barrel in definition.barrels {
+                let mut barrel_transform = *transform;
+                barrel_transform.translation +=
+                    transform.rotation * Vec3::new(barrel.offset, MUZZLE_OFFSET, 0.0);
+                barrel_transform.rotation =
+                    transform.rotation * Quat::from_rotation_z(barrel.angle);
+                laser_spawn_events.write(LaserSpawnMessage {
+                    transform: barrel_transform,
+                    linvel: *linvel,
+                    owner: entity,
+                    weapon: weapon.kind,
+                });
+            }
+            match audios.weapon_triggers.get(&weapon.kind) {
+                Some(sound) => {
+                    commands.spawn((AudioPlayer(sound.clone()), PlaybackSettings::DESPAWN))
+                }
+                None => commands.spawn((
+                    AudioPlayer(audios.laser_trigger.clone()),
+                    PlaybackSettings::DESPAWN,
+                )),
+            };
+            weapon.cooldown_timer.reset();
+        }
+    }
+}